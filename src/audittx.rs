@@ -1,6 +1,30 @@
 use super::*;
 use std::cmp::Ordering;
 
+/// BIP68 relative-locktime constraints for a tx, analogous to Bitcoin Core's
+/// `LockPoints`. Defaults to all-zero, i.e. final at any height/time, for pools that
+/// don't populate it.
+///
+/// No `Audit` impl in this crate currently populates this from real chain data --
+/// doing so needs each input's confirmation height/mtp, which in turn needs resolved
+/// prevouts (see `crate::resolve_prevouts`) run through BIP68's sequence-lock
+/// calculation. Until a caller wires that up, every tx built via `Audit::into_pool`
+/// is final at any height/time, and the block assembler's deferral logic only
+/// exercises on hand-built `LockPoints` (e.g. in tests).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LockPoints {
+    /// Minimum chain height at which all of this tx's relative-locktime sequence locks
+    /// are satisfied
+    pub height: u64,
+    /// Minimum median-time-past at which all of this tx's relative-locktime sequence
+    /// locks are satisfied
+    pub time: u64,
+    /// Highest block containing one of the inputs used to compute `height`/`time`, so a
+    /// cached lock point stays valid across reorgs so long as the active chain still
+    /// descends from it
+    pub max_input_block: u64,
+}
+
 /// Transaction metadata used for scoring packages during tx selection
 #[derive(Clone, Debug)]
 pub struct AuditTx {
@@ -9,13 +33,23 @@ pub struct AuditTx {
     pub fee: u64,
     pub weight: u64,
     pub feerate: f64,
-    //sigops: u32,
+    pub sigops: u32,
     pub parents: HashSet<usize>,
     pub ancestors: HashSet<usize>,
     pub children: HashSet<usize>,
     pub ancestor_fee: u64,
     pub ancestor_weight: u64,
-    //ancestor_sigops: u32,
+    pub ancestor_sigops: u32,
+    /// Cumulative fee bump applied by `prioritise_transaction`, as in Bitcoin Core's
+    /// `prioritisetransaction`
+    pub fee_delta: i64,
+    /// Aggregate fee of this tx plus all of its in-pool descendants, kept up to date by
+    /// `prioritise_transaction` so a prioritised descendant is reflected in its ancestors'
+    /// package attractiveness
+    pub descendant_fee: u64,
+    /// BIP68 relative-locktime constraints, consulted by the block-assembly selection
+    /// loop so a still-immature tx is deferred rather than dropped
+    pub lock_points: LockPoints,
     pub score: f64,
     pub used: bool,
     pub modified: bool,
@@ -31,13 +65,16 @@ impl Default for AuditTx {
             fee: 0,
             weight: 0,
             feerate: 0.0,
-            //sigops: 0,
+            sigops: 0,
             parents: HashSet::default(),
             ancestors: HashSet::default(),
             children: HashSet::default(),
             ancestor_fee: 0,
             ancestor_weight: 0,
-            //ancestor_sigops: 0,
+            ancestor_sigops: 0,
+            fee_delta: 0,
+            descendant_fee: 0,
+            lock_points: LockPoints::default(),
             score: 0.0,
             used: false,
             modified: false,
@@ -54,7 +91,8 @@ impl AuditTx {
         self.feerate = feerate;
         self.ancestor_fee = self.fee;
         self.ancestor_weight = self.weight;
-        //self.ancestor_sigops = self.sigops;
+        self.ancestor_sigops = self.sigops;
+        self.descendant_fee = self.fee;
         self.score = feerate;
         self.links_set = self.parents.is_empty();
     }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::audittx::{AuditTx, TxPriority};
+use crate::audittx::{AuditTx, LockPoints, TxPriority};
 use bitcoin::hashes::Hash;
 use bitcoin::Amount;
 use bitcoin::Txid;
@@ -8,23 +8,79 @@ use serde::Serialize;
 use std::cmp::Ordering;
 use std::time;
 
-/// Maximum block weight
+/// Default maximum block weight. Overridable per [`BlockAssembler`] via its `max_block_wu` field.
 const MAX_BLOCK_WU: u64 = 4_000_000;
-/// Number of attempts to fit a package in a block before considering it full
+/// Maximum block sigops cost (legacy sigops scaled x4, witness sigops x1)
+const MAX_BLOCK_SIGOPS_COST: u64 = 80_000;
+/// Default number of attempts to fit a package in a block before considering it full.
+/// Overridable per [`BlockAssembler`] via its `max_failures` field.
 const MAX_FAILURES: usize = 500;
-/// The most blocks that `BlockAssembler` will build, provided sufficient inventory.
+/// The default most blocks that `BlockAssembler` will build, provided sufficient
+/// inventory. Overridable per [`BlockAssembler`] via its `block_goal` field, e.g. to
+/// drive the projection loop past two blocks for multi-target feerate estimation.
 const BLOCK_GOAL: usize = 2;
 
+/// Strategy used to select which tx (or package) is considered next when filling a
+/// block, letting the tool model miners that don't run full ancestor-package selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum OrderingStrategy {
+    /// Sort purely by in-mempool entry order (derived from txid bytes), approximating
+    /// first-seen/timestamp ordering
+    ByEntryOrder,
+    /// Sort purely on `fee / (weight / 4)` per tx, ignoring ancestor aggregation
+    ByIndividualFeerate,
+    /// Sort by ancestor ("package") score, with the modified queue tracking CPFP-adjusted
+    /// scores as ancestors are included. This is the default, consensus-accurate policy.
+    #[default]
+    ByPackageScore,
+}
+
+/// A tx's own standalone feerate, including any `fee_delta` applied by
+/// `prioritise_transaction`
+fn effective_feerate(tx: &AuditTx) -> f64 {
+    (tx.fee as i64 + tx.fee_delta) as f64 / (tx.weight as f64 / 4.0)
+}
+
+/// Computes the `(key, order, uid)` tuple used to rank `tx` under the given
+/// [`OrderingStrategy`]. `ByPackageScore` ranks on the CPFP-adjusted ancestor score
+/// (today's behavior); `ByIndividualFeerate` ranks purely on the tx's own feerate,
+/// ignoring ancestor aggregation; `ByEntryOrder` ranks solely by entry order,
+/// approximating first-seen ordering.
+fn ordering_key_for(strategy: OrderingStrategy, tx: &AuditTx) -> (f64, u32, usize) {
+    match strategy {
+        OrderingStrategy::ByPackageScore => (tx.score, tx.order, tx.uid),
+        OrderingStrategy::ByIndividualFeerate => (tx.feerate, tx.order, tx.uid),
+        OrderingStrategy::ByEntryOrder => (0.0, u32::MAX - tx.order, tx.uid),
+    }
+}
+
 /// Type for managing block assembly
 struct BlockAssembler {
     pool: AuditPool,
     next_height: u64,
     fees: u64,
     weight: u64,
+    sigops: u64,
     inv: Inventory,
     blocks: Vec<BlockSummary>,
     modified: PriorityQueue<usize, TxPriority>,
     overflow: Vec<usize>,
+    strategy: OrderingStrategy,
+    /// Maximum block weight. Defaults to [`MAX_BLOCK_WU`].
+    max_block_wu: u64,
+    /// Number of attempts to fit a package in a block before considering it full.
+    /// Defaults to [`MAX_FAILURES`].
+    max_failures: usize,
+    /// The most blocks that `generate` will build, provided sufficient inventory.
+    /// Defaults to [`BLOCK_GOAL`]; set by [`estimate_feerates_with_strategy`] to the
+    /// largest requested confirmation target (at least 1), to drive the projection loop
+    /// out to it.
+    block_goal: usize,
+    /// Median-time-past consulted against each tx's [`LockPoints::time`]. Defaults to
+    /// `u64::MAX`, i.e. no tx is deferred on time grounds unless this is set. No
+    /// constructor in this crate sets it from a real chain tip yet -- see the caveat on
+    /// [`LockPoints`].
+    chain_mtp: u64,
 }
 
 /// Data the [`BlockAssembler`] keeps track of while generating blocks
@@ -56,9 +112,13 @@ pub struct BlockSummary {
     /// Median effective feerate
     #[serde(skip_serializing_if = "Option::is_none")]
     pub median_effective_feerate: Option<f64>,
+    /// Block sigops cost
+    pub sigops: u64,
     /// Ancestor score distribution
     #[serde(skip)]
     pub fee_histogram: Option<FeeHistogram>,
+    /// The [`OrderingStrategy`] used to select transactions for this block
+    pub strategy: OrderingStrategy,
 }
 
 /// Defines the distribution of transaction weight across twelve feerate buckets
@@ -155,10 +215,12 @@ impl Cluster for BlockAssembler {
         // count ancestor data
         let mut ancestor_fee = 0u64;
         let mut ancestor_weight = 0u64;
+        let mut ancestor_sigops = 0u32;
         for ancestor_id in &ancestors {
             let ancestor = self.pool.get(ancestor_id).expect("uid exists");
             ancestor_fee += ancestor.fee;
             ancestor_weight += ancestor.weight;
+            ancestor_sigops += ancestor.sigops;
         }
 
         // score this tx
@@ -166,7 +228,12 @@ impl Cluster for BlockAssembler {
         tx.ancestors = ancestors;
         tx.ancestor_fee += ancestor_fee;
         tx.ancestor_weight += ancestor_weight;
-        tx.score = tx.ancestor_fee as f64 / (tx.ancestor_weight as f64 / 4.0);
+        tx.ancestor_sigops += ancestor_sigops;
+        // Cap the package score at the tx's own feerate: once its high-fee ancestors are
+        // mined, a low-fee tx is worth no more than its own standalone feerate, matching
+        // Core's `ancestor_score` index behavior.
+        let ancestor_package_feerate = tx.ancestor_fee as f64 / (tx.ancestor_weight as f64 / 4.0);
+        tx.score = effective_feerate(tx).min(ancestor_package_feerate);
         tx.links_set = true;
     }
 }
@@ -179,10 +246,16 @@ impl BlockAssembler {
             next_height: 0,
             fees: 0,
             weight: 4000,
+            sigops: 0,
             inv: Inventory::default(),
             blocks: vec![],
             modified: PriorityQueue::new(),
             overflow: vec![],
+            strategy: OrderingStrategy::default(),
+            max_block_wu: MAX_BLOCK_WU,
+            max_failures: MAX_FAILURES,
+            block_goal: BLOCK_GOAL,
+            chain_mtp: u64::MAX,
         }
     }
 
@@ -194,11 +267,13 @@ impl BlockAssembler {
         maker
     }
 
-    /// Creates a [`BlockAssembler`] from a given `audit_pool` and block `height`
-    fn from_pool_with_height(audit_pool: AuditPool, height: u64) -> Self {
+    /// Creates a [`BlockAssembler`] from a given `audit_pool` and block `height`, selecting
+    /// tx for inclusion according to `strategy`
+    fn from_pool_with_height(audit_pool: AuditPool, height: u64, strategy: OrderingStrategy) -> Self {
         let mut maker = BlockAssembler::new();
         maker.pool = audit_pool;
         maker.next_height = height;
+        maker.strategy = strategy;
         maker
     }
 
@@ -206,19 +281,43 @@ impl BlockAssembler {
     fn clear(&mut self) {
         self.fees = 0;
         self.weight = 4000;
+        self.sigops = 0;
         self.inv = Inventory::default();
         self.next_height += 1;
     }
 
-    /// Whether the current block is at capacity (99.9%)
+    /// Whether the current block is at capacity (99.9% of either weight or sigops budget)
     fn is_full(&self) -> bool {
-        let margin = MAX_BLOCK_WU / 1000;
-        self.weight >= (MAX_BLOCK_WU - margin)
+        let weight_margin = self.max_block_wu / 1000;
+        let sigops_margin = MAX_BLOCK_SIGOPS_COST / 1000;
+        self.weight >= (self.max_block_wu - weight_margin)
+            || self.sigops >= (MAX_BLOCK_SIGOPS_COST - sigops_margin)
     }
 
-    /// Test if the given package will fit in the candidate block
+    /// Test if the given package will fit in the candidate block, respecting both the
+    /// block weight limit and the block sigops-cost limit
     fn test_package_fits(&self, tx: &AuditTx) -> bool {
-        self.weight + tx.ancestor_weight < MAX_BLOCK_WU
+        self.weight + tx.ancestor_weight < self.max_block_wu
+            && self.sigops + u64::from(tx.ancestor_sigops) < MAX_BLOCK_SIGOPS_COST
+    }
+
+    /// Whether `tx`'s own BIP68 [`LockPoints`] are satisfied by the block currently
+    /// being built
+    fn is_final(&self, tx: &AuditTx) -> bool {
+        self.next_height >= tx.lock_points.height && self.chain_mtp >= tx.lock_points.time
+    }
+
+    /// Whether `tx` and every ancestor in its package are spendable at the block
+    /// currently being built. A package that fails this check is deferred (pushed to
+    /// `overflow`, same as one that doesn't fit) rather than dropped, so a later block --
+    /// built at a higher height/mtp -- can include it once its lock points pass.
+    fn package_is_final(&self, tx: &AuditTx) -> bool {
+        self.is_final(tx)
+            && tx
+                .ancestors
+                .iter()
+                .filter_map(|uid| self.pool.get(uid))
+                .all(|ancestor| self.is_final(ancestor))
     }
 
     /// Select the given `tx` and its ancestors for inclusion in a block,
@@ -251,6 +350,7 @@ impl BlockAssembler {
                 tx.used = true;
                 self.weight += tx.weight;
                 self.fees += tx.fee;
+                self.sigops += u64::from(tx.sigops);
                 self.inv.lo_score = self.inv.lo_score.min(tx.score);
                 self.inv.hi_score = self.inv.hi_score.max(tx.score);
                 self.inv.scores.push(tx.score);
@@ -271,9 +371,10 @@ impl BlockAssembler {
         if is_full {
             height = Some(self.next_height);
             fee_histogram = Some(self.histogram_generate(&txn));
-            self.inv.scores.sort_by(|a, b| a.total_cmp(b));
-            let scores = &self.inv.scores;
-            median_effective_feerate = Some(median_from_sorted(scores));
+            if !self.inv.scores.is_empty() {
+                self.inv.scores.sort_by(|a, b| a.total_cmp(b));
+                median_effective_feerate = Some(median_from_sorted(&self.inv.scores));
+            }
         }
 
         let fee_range = {
@@ -290,20 +391,36 @@ impl BlockAssembler {
             fees: Amount::from_sat(self.fees).to_btc(),
             fee_range,
             median_effective_feerate,
+            sigops: self.sigops,
             fee_histogram,
+            strategy: self.strategy,
         }
     }
 
+    /// Computes the `(key, order, uid)` tuple used to rank `tx` under this assembler's
+    /// [`OrderingStrategy`]
+    fn ordering_key(&self, tx: &AuditTx) -> (f64, u32, usize) {
+        ordering_key_for(self.strategy, tx)
+    }
+
+    /// Compares two tx under this assembler's [`OrderingStrategy`]
+    fn compare_tx(&self, a: &AuditTx, b: &AuditTx) -> Ordering {
+        compare_audit_tx(self.ordering_key(a), self.ordering_key(b))
+    }
+
     /// Generates block projections provided `self` has data to work on
     fn generate(mut self) -> Vec<BlockSummary> {
         let start = time::Instant::now();
-        for uid in 0..self.pool.len() {
+        // Collect uids up front: `insert_tx`/`remove_tx` may leave gaps in the uid
+        // space, so this can't assume a dense `0..self.pool.len()` range.
+        let uids: Vec<usize> = self.pool.keys().copied().collect();
+        for uid in uids {
             self.set_links(uid);
         }
 
-        // Sort by ancestor score (ascending), and create a stack of uids
+        // Sort by this assembler's ordering strategy (ascending), and create a stack of uids
         let mut pool_stack: Vec<&AuditTx> = self.pool.values().collect();
-        pool_stack.sort();
+        pool_stack.sort_by(|a, b| self.compare_tx(a, b));
         let mut pool_stack: Vec<usize> = pool_stack.into_iter().map(|tx| tx.uid).collect();
 
         // Build blocks
@@ -323,7 +440,7 @@ impl BlockAssembler {
                     tx
                 }
                 (Some(tx), Some(modtx)) => {
-                    match tx.cmp(modtx) {
+                    match self.compare_tx(tx, modtx) {
                         Ordering::Equal => {
                             self.modified.pop();
                             pool_stack.pop(); // drop duplicates
@@ -341,8 +458,10 @@ impl BlockAssembler {
                 }
             };
 
-            // Check if this package fits, or if we're done building blocks, continue on packages until queues empty
-            if self.test_package_fits(tx) || self.blocks.len() >= BLOCK_GOAL {
+            // Check if this package fits and is final, or if we're done building blocks,
+            // continue on packages until queues empty. A non-final package is deferred
+            // the same way an oversized one is, so a later (higher) block can pick it up.
+            if self.package_is_final(tx) && (self.test_package_fits(tx) || self.blocks.len() >= self.block_goal) {
                 let package = self.add_package_tx(tx);
                 for uid in package {
                     let tx = self.pool.get(&uid).expect("uid exists");
@@ -356,9 +475,9 @@ impl BlockAssembler {
                 self.inv.failures += 1;
             }
 
-            let exceeded_attempts = self.inv.failures >= MAX_FAILURES && self.is_full();
+            let exceeded_attempts = self.inv.failures >= self.max_failures && self.is_full();
             let queue_empty = pool_stack.is_empty() && self.modified.is_empty();
-            if (exceeded_attempts || queue_empty) && self.blocks.len() < BLOCK_GOAL {
+            if (exceeded_attempts || queue_empty) && self.blocks.len() < self.block_goal {
                 // Build this block
                 let block = self.make_block(/*is_full: */ true);
                 self.blocks.push(block);
@@ -371,14 +490,8 @@ impl BlockAssembler {
                         continue;
                     }
                     if tx.modified {
-                        self.modified.push_increase(
-                            tx.uid,
-                            TxPriority {
-                                uid: tx.uid,
-                                order: tx.order,
-                                score: tx.score,
-                            },
-                        );
+                        let (score, order, uid) = self.ordering_key(tx);
+                        self.modified.push_increase(uid, TxPriority { uid, order, score });
                     } else {
                         pool_stack.push(tx.uid);
                     }
@@ -386,6 +499,24 @@ impl BlockAssembler {
             }
         }
 
+        // A package still in `overflow` here was deferred for failing `package_is_final`
+        // after the last bounded block was built. It gets one last check at the current
+        // `next_height` (`chain_mtp` is never advanced); a package that still fails it
+        // is left out of every block.
+        while let Some(uid) = self.overflow.pop() {
+            let tx = self.pool.get(&uid).expect("uid exists").clone();
+            if tx.used || !self.package_is_final(&tx) {
+                continue;
+            }
+            let package = self.add_package_tx(&tx);
+            for uid in package {
+                let tx = self.pool.get(&uid).expect("uid exists");
+                if !tx.children.is_empty() {
+                    self.update_descendants(tx.uid);
+                }
+            }
+        }
+
         if !self.inv.txn.is_empty() {
             // Collect remaining tx in a final unbounded block
             let block = self.make_block(false);
@@ -400,6 +531,7 @@ impl BlockAssembler {
 
     /// Walk remaining descendants, removing this ancestor `uid` and updating scores
     fn update_descendants(&mut self, uid: usize) {
+        let strategy = self.strategy;
         let mut visited = vec![];
         let mut descendant_stack = vec![];
 
@@ -407,6 +539,7 @@ impl BlockAssembler {
         let ancestor = self.pool.get(&uid).expect("uid exist");
         let root_fee = ancestor.fee;
         let root_weight = ancestor.weight;
+        let root_sigops = ancestor.sigops;
         for child in &ancestor.children {
             if !visited.contains(child) {
                 descendant_stack.push(*child);
@@ -429,30 +562,122 @@ impl BlockAssembler {
             if tx.ancestors.remove(&uid) {
                 tx.ancestor_fee -= root_fee;
                 tx.ancestor_weight -= root_weight;
+                tx.ancestor_sigops -= root_sigops;
                 let old_score = tx.score;
-                tx.score = tx.ancestor_fee as f64 / (tx.ancestor_weight as f64 / 4.0);
+                let ancestor_package_feerate = tx.ancestor_fee as f64 / (tx.ancestor_weight as f64 / 4.0);
+                tx.score = effective_feerate(tx).min(ancestor_package_feerate);
+
+                // Add or update modified queue. Only `ByPackageScore` selects on the
+                // ancestor score, so other strategies leave the modified queue untouched.
+                if strategy == OrderingStrategy::ByPackageScore {
+                    let (score, order, uid) = ordering_key_for(strategy, tx);
+                    if tx.score < old_score {
+                        tx.modified = true;
+                        self.modified.push_decrease(uid, TxPriority { uid, order, score });
+                    } else if tx.score > old_score {
+                        tx.modified = true;
+                        self.modified.push_increase(uid, TxPriority { uid, order, score });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Inserts a newly-seen `tx` into the pool, wiring its ancestor links and score via
+    /// [`Self::set_links`]. `tx` is expected to have already had [`AuditTx::pre_fill`]
+    /// called (as [`Audit::into_pool`] does for a fresh pool); unlike [`Self::update_descendants`],
+    /// a brand-new tx has no descendants yet, so there's nothing to propagate downward —
+    /// only its own ancestor totals and its parents' `children` sets need wiring up.
+    fn insert_tx(&mut self, tx: AuditTx) {
+        let uid = tx.uid;
+        self.pool.insert(uid, tx);
+        self.set_links(uid);
+    }
+
+    /// Removes `uid` together with its full descendant cone, so a live pool can drop a
+    /// replaced or expired tx without leaving any remaining entry referencing a missing
+    /// parent. Descendants are removed depth-first (a tx is only dropped once all of its
+    /// children are already gone), then `uid` itself.
+    fn remove_tx(&mut self, uid: usize) {
+        let Some(tx) = self.pool.get(&uid) else {
+            return;
+        };
+        let children: Vec<usize> = tx.children.iter().copied().collect();
+        for child in children {
+            self.remove_tx(child);
+        }
+
+        // All descendants are gone; unlink `uid` from its direct parents before dropping it.
+        let parents: Vec<usize> = self
+            .pool
+            .get(&uid)
+            .expect("uid exists")
+            .parents
+            .iter()
+            .copied()
+            .collect();
+        for parent_id in parents {
+            if let Some(parent) = self.pool.get_mut(&parent_id) {
+                parent.children.remove(&uid);
+            }
+        }
+
+        self.pool.remove(&uid);
+        self.modified.remove(&uid);
+    }
+
+    /// Bumps `uid`'s mining priority by `fee_delta`, as in Bitcoin Core's
+    /// `prioritisetransaction`, and propagates the change both directions so neither side
+    /// is left scored against a stale fee total — the bug that motivated this in Core:
+    ///
+    /// - every ancestor's aggregate `descendant_fee` is bumped, so the ancestor's own
+    ///   package looks more attractive once this tx (and its descendants) are considered
+    /// - `uid` itself and every one of its descendants have their `ancestor_fee` bumped
+    ///   and `score` recomputed, re-heaping the `modified` queue so a descendant selected
+    ///   after `uid` inherits the boosted ancestor fee rather than the raw one
+    fn prioritise_transaction(&mut self, uid: usize, fee_delta: i64) {
+        let Some(tx) = self.pool.get_mut(&uid) else {
+            return;
+        };
+        tx.fee_delta += fee_delta;
+        let ancestors: Vec<usize> = tx.ancestors.iter().copied().collect();
+
+        // Ancestor pass: bump the descendant-fee total they'd see if this tx's package
+        // were pulled in with them.
+        for ancestor_id in ancestors {
+            if let Some(ancestor) = self.pool.get_mut(&ancestor_id) {
+                ancestor.descendant_fee = (ancestor.descendant_fee as i64 + fee_delta).max(0) as u64;
+            }
+        }
 
-                // Add or update modified queue
+        // Descendant pass (inclusive of `uid` itself): bump the ancestor-fee total used
+        // for `score`, and re-heap the modified queue so selection order reflects it.
+        let strategy = self.strategy;
+        let mut visited = vec![uid];
+        let mut stack = vec![uid];
+        while let Some(next) = stack.pop() {
+            let tx = self.pool.get_mut(&next).expect("uid exists");
+            let children: Vec<usize> = tx.children.iter().copied().collect();
+            tx.ancestor_fee = (tx.ancestor_fee as i64 + fee_delta).max(0) as u64;
+            let old_score = tx.score;
+            let ancestor_package_feerate = tx.ancestor_fee as f64 / (tx.ancestor_weight as f64 / 4.0);
+            tx.score = effective_feerate(tx).min(ancestor_package_feerate);
+
+            if strategy == OrderingStrategy::ByPackageScore {
+                let (score, order, uid) = ordering_key_for(strategy, tx);
                 if tx.score < old_score {
                     tx.modified = true;
-                    self.modified.push_decrease(
-                        tx.uid,
-                        TxPriority {
-                            uid: tx.uid,
-                            order: tx.order,
-                            score: tx.score,
-                        },
-                    );
+                    self.modified.push_decrease(uid, TxPriority { uid, order, score });
                 } else if tx.score > old_score {
                     tx.modified = true;
-                    self.modified.push_increase(
-                        tx.uid,
-                        TxPriority {
-                            uid: tx.uid,
-                            order: tx.order,
-                            score: tx.score,
-                        },
-                    );
+                    self.modified.push_increase(uid, TxPriority { uid, order, score });
+                }
+            }
+
+            for child in children {
+                if !visited.contains(&child) {
+                    visited.push(child);
+                    stack.push(child);
                 }
             }
         }
@@ -460,13 +685,245 @@ impl BlockAssembler {
 }
 
 /* Called from main */
-/// Produce a fee report from the given mempool entries and block height
+/// Produce a fee report from the given mempool entries and block height, selecting tx
+/// for inclusion via the default [`OrderingStrategy::ByPackageScore`]
 pub fn audit_fees(height: u64, entries: impl Audit) -> Vec<BlockSummary> {
+    audit_fees_with_strategy(height, entries, OrderingStrategy::default())
+}
+
+/// Produce a fee report from the given mempool entries and block height, selecting tx
+/// for inclusion according to `strategy`
+pub fn audit_fees_with_strategy(
+    height: u64,
+    entries: impl Audit,
+    strategy: OrderingStrategy,
+) -> Vec<BlockSummary> {
+    let (_index, pool) = entries.into_pool();
+    let maker = BlockAssembler::from_pool_with_height(pool, height, strategy);
+    maker.generate()
+}
+
+/// Produce a fee report from the given mempool entries and block height, additionally
+/// populating and enforcing each tx's sigops cost against the consensus block sigops
+/// budget, so the projection only admits packages a real miner could include.
+pub fn audit_fees_with_sigops(
+    height: u64,
+    entries: impl Audit,
+    core: &Client,
+) -> Result<Vec<BlockSummary>> {
+    audit_fees_with_sigops_and_strategy(height, entries, core, OrderingStrategy::default())
+}
+
+/// Like [`audit_fees_with_sigops`], additionally selecting tx for inclusion according
+/// to `strategy`
+pub fn audit_fees_with_sigops_and_strategy(
+    height: u64,
+    entries: impl Audit,
+    core: &Client,
+    strategy: OrderingStrategy,
+) -> Result<Vec<BlockSummary>> {
+    let (index, mut pool) = entries.into_pool();
+    for (txid, uid) in &index {
+        let Ok(tx_info) = core.get_raw_transaction_info_verbose(txid, None) else {
+            continue;
+        };
+        if let Some(tx) = pool.get_mut(uid) {
+            tx.sigops = sigops::get_sigops_count(&tx_info);
+            tx.ancestor_sigops = tx.sigops;
+        }
+    }
+    let maker = BlockAssembler::from_pool_with_height(pool, height, strategy);
+    Ok(maker.generate())
+}
+
+/// A feerate estimate for confirming within a given number of blocks
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FeeEstimate {
+    /// Confirmation target, in blocks
+    pub target: u64,
+    /// Minimum effective feerate (sat/vB) a tx would need to confirm within `target` blocks
+    pub feerate: f64,
+}
+
+/// Estimates the minimum effective feerate needed to confirm within each of the given
+/// confirmation `targets` (in blocks), selecting tx for inclusion via the default
+/// [`OrderingStrategy::ByPackageScore`]
+pub fn estimate_feerates(entries: impl Audit, targets: &[u64]) -> Vec<FeeEstimate> {
+    estimate_feerates_with_strategy(entries, targets, OrderingStrategy::default())
+}
+
+/// Estimates the minimum effective feerate needed to confirm within each of the given
+/// confirmation `targets` (in blocks), selecting tx for inclusion according to `strategy`.
+///
+/// Builds bounded block projections out to the largest target, then reads off each
+/// target's minimum effective feerate as the `lo_score` (`fee_range.0`) of its block,
+/// mirroring how mempool explorers turn ancestor-score block templates into a fee table.
+pub fn estimate_feerates_with_strategy(
+    entries: impl Audit,
+    targets: &[u64],
+    strategy: OrderingStrategy,
+) -> Vec<FeeEstimate> {
     let (_index, pool) = entries.into_pool();
-    let maker = BlockAssembler::from_pool_with_height(pool, height);
+    let mut maker = BlockAssembler::from_pool_with_height(pool, 0, strategy);
+    let max_target = targets.iter().copied().max().unwrap_or(1);
+    maker.block_goal = usize::try_from(max_target).unwrap_or(usize::MAX).max(1);
+    let blocks = maker.generate();
+
+    targets
+        .iter()
+        .map(|&target| {
+            let feerate = usize::try_from(target)
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|idx| blocks.get(idx))
+                .map_or(0.0, |block| block.fee_range.0);
+            FeeEstimate { target, feerate }
+        })
+        .collect()
+}
+
+/// Produces a fee report as if `exclude` (and its full descendant cone) had already been
+/// evicted from the mempool, via [`BlockAssembler::remove_tx`] -- e.g. to project the
+/// effect of a confirmed or conflicting tx disappearing without waiting for the next poll
+/// to observe it gone. An `exclude` not present in `entries` is a no-op.
+pub fn audit_fees_excluding(
+    height: u64,
+    entries: impl Audit,
+    strategy: OrderingStrategy,
+    exclude: Txid,
+) -> Vec<BlockSummary> {
+    let (index, pool) = entries.into_pool();
+    let mut maker = BlockAssembler::from_pool_with_height(pool, height, strategy);
+    if let Some(&uid) = index.get(&exclude) {
+        maker.remove_tx(uid);
+    }
     maker.generate()
 }
 
+/// Produces a fee report with a hypothetical, not-yet-broadcast `tx` injected into the
+/// pool via [`BlockAssembler::insert_tx`], for projecting where a standalone package
+/// would land. `tx` is assumed to have no in-pool parents; its `fee`/`weight` should
+/// already be set, as [`AuditTx::pre_fill`] is run on it here.
+pub fn audit_fees_including(
+    height: u64,
+    entries: impl Audit,
+    strategy: OrderingStrategy,
+    mut tx: AuditTx,
+) -> Vec<BlockSummary> {
+    let (_index, pool) = entries.into_pool();
+    tx.uid = pool.keys().copied().max().map_or(0, |max_uid| max_uid + 1);
+    tx.pre_fill();
+
+    let mut maker = BlockAssembler::from_pool_with_height(pool, height, strategy);
+    maker.insert_tx(tx);
+    maker.generate()
+}
+
+/// Produces a fee report after bumping `txid`'s mining priority by `fee_delta` via
+/// [`BlockAssembler::prioritise_transaction`], as in Bitcoin Core's `prioritisetransaction`.
+/// A `txid` not present in `entries` is a no-op.
+pub fn audit_fees_with_priority(
+    height: u64,
+    entries: impl Audit,
+    strategy: OrderingStrategy,
+    txid: Txid,
+    fee_delta: i64,
+) -> Vec<BlockSummary> {
+    let (index, pool) = entries.into_pool();
+    let mut maker = BlockAssembler::from_pool_with_height(pool, height, strategy);
+    if let Some(&uid) = index.get(&txid) {
+        maker.prioritise_transaction(uid, fee_delta);
+    }
+    maker.generate()
+}
+
+/// Aggregate package stats for a single mempool entry, analogous to Core's
+/// `getmempoolentry`. `ancestor_count`/`descendant_count` exclude `uid` itself;
+/// `package_size` and `package_fee` cover `uid` plus its full ancestor set, with
+/// `package_fee` reflecting any `prioritise_transaction` adjustment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EntryStats {
+    pub ancestor_count: usize,
+    pub descendant_count: usize,
+    pub package_size: u64,
+    pub package_fee: u64,
+}
+
+/// Returns the full, transitive set of in-pool ancestor uids for `uid`, walking `parents`
+/// links directly rather than reading the pool's cached [`AuditTx::ancestors`] set --
+/// useful both for querying a pool that hasn't had `set_links` run on it yet, and as an
+/// independent check on the incremental bookkeeping `set_links`/`update_descendants` do.
+/// Analogous to Core's `getmempoolancestors`.
+pub fn ancestors(pool: &AuditPool, uid: usize) -> HashSet<usize> {
+    let mut seen = HashSet::new();
+    let mut stack: Vec<usize> = pool
+        .get(&uid)
+        .map(|tx| tx.parents.iter().copied().collect())
+        .unwrap_or_default();
+    while let Some(next) = stack.pop() {
+        if seen.insert(next) {
+            if let Some(tx) = pool.get(&next) {
+                stack.extend(tx.parents.iter().copied());
+            }
+        }
+    }
+    seen
+}
+
+/// Returns the full, transitive set of in-pool descendant uids for `uid`, walking
+/// `children` links. Analogous to Core's `getmempooldescendants`.
+pub fn descendants(pool: &AuditPool, uid: usize) -> HashSet<usize> {
+    let mut seen = HashSet::new();
+    let mut stack: Vec<usize> = pool
+        .get(&uid)
+        .map(|tx| tx.children.iter().copied().collect())
+        .unwrap_or_default();
+    while let Some(next) = stack.pop() {
+        if seen.insert(next) {
+            if let Some(tx) = pool.get(&next) {
+                stack.extend(tx.children.iter().copied());
+            }
+        }
+    }
+    seen
+}
+
+/// Computes [`EntryStats`] for `uid` by independently re-walking the ancestor/descendant
+/// graph via [`ancestors`]/[`descendants`], rather than trusting the pool's cached
+/// `ancestor_fee`/`ancestor_weight` totals -- so callers (and tests) can cross-check the
+/// latter. Returns `None` if `uid` isn't in the pool.
+pub fn entry_stats(pool: &AuditPool, uid: usize) -> Option<EntryStats> {
+    let tx = pool.get(&uid)?;
+    let ancestor_set = ancestors(pool, uid);
+    let descendant_set = descendants(pool, uid);
+
+    let mut package_size = tx.weight;
+    let mut package_fee = (tx.fee as i64 + tx.fee_delta).max(0) as u64;
+    for ancestor in ancestor_set.iter().filter_map(|id| pool.get(id)) {
+        package_size += ancestor.weight;
+        package_fee += (ancestor.fee as i64 + ancestor.fee_delta).max(0) as u64;
+    }
+
+    Some(EntryStats {
+        ancestor_count: ancestor_set.len(),
+        descendant_count: descendant_set.len(),
+        package_size,
+        package_fee,
+    })
+}
+
+/// Iterates the pool in descending order of raw, un-prioritised `fee/size` -- each tx's
+/// plain [`AuditTx::feerate`], which `prioritise_transaction` deliberately leaves
+/// untouched -- tie-broken by ascending uid. Models how peers actually see this mempool
+/// relayed/evicted, independent of any local `fee_delta` a miner applies only for its own
+/// block template; a node's private prioritisation shouldn't be observable through
+/// iteration order.
+pub fn iter_by_relay_feerate(pool: &AuditPool) -> impl Iterator<Item = &AuditTx> + '_ {
+    let mut txs: Vec<&AuditTx> = pool.values().collect();
+    txs.sort_by(|a, b| b.feerate.total_cmp(&a.feerate).then_with(|| a.uid.cmp(&b.uid)));
+    txs.into_iter()
+}
+
 /**  Helpers */
 impl BlockAssembler {
     /// Iterate audit pool returning an option to the first unused tx (cloned), else None
@@ -554,6 +1011,7 @@ mod test {
             uid,
             fee,
             weight: 800,
+            sigops: 0,
             parents,
         }
     }
@@ -596,12 +1054,30 @@ mod test {
         assert_eq!(uids, expect);
     }
 
+    #[test]
+    fn entry_order_strategy_ignores_fee() {
+        // By fee these rank 0, 2, 1; the entry order derived from uid (order == uid
+        // here) ranks them 0, 1, 2, lowest order first
+        let (_, pool) = vec![
+            entry_with_fee_and_parent(0, 4000, None),
+            entry_with_fee_and_parent(1, 1000, None),
+            entry_with_fee_and_parent(2, 2000, None),
+        ]
+        .into_pool();
+
+        let maker = BlockAssembler::from_pool_with_height(pool, 0, OrderingStrategy::ByEntryOrder);
+        let blocks = maker.generate();
+        // preserves entry order (0, 1, 2) rather than ranking by fee (0, 2, 1)
+        assert_eq!(blocks[0].txn, vec![0, 1, 2]);
+    }
+
     #[test]
     fn make_single() {
         let (_, pool) = vec![TestMempoolEntry {
             uid: 0usize,
             fee: 1000,
             weight: 840,
+            sigops: 0,
             parents: HashSet::new(),
         }]
         .into_pool();
@@ -649,6 +1125,7 @@ mod test {
                 uid: i,
                 fee: 100_000,
                 weight: 396_000,
+                sigops: 0,
                 parents: HashSet::new(),
             });
         }
@@ -667,6 +1144,30 @@ mod test {
         }
     }
 
+    #[test]
+    fn sigops_budget_caps_block_before_weight_does() {
+        // Each tx is weight-negligible but carries 20_000 sigops, so only 3 fit per
+        // block under MAX_BLOCK_SIGOPS_COST (80_000) well before MAX_BLOCK_WU would bind.
+        let mut entries: Vec<TestMempoolEntry> = vec![];
+        for i in 0usize..8 {
+            entries.push(TestMempoolEntry {
+                uid: i,
+                fee: 1000,
+                weight: 800,
+                sigops: 20_000,
+                parents: HashSet::new(),
+            });
+        }
+        let maker = BlockAssembler::from(entries.into_pool().1);
+        let blocks = maker.generate();
+
+        // 2 sigops-capped blocks of 3 tx each, +1 unbounded block with the remaining 2
+        assert_eq!(blocks[0].tx_count, 3);
+        assert_eq!(blocks[0].sigops, 60_000);
+        assert_eq!(blocks[1].tx_count, 3);
+        assert_eq!(blocks[2].tx_count, 2);
+    }
+
     #[test]
     fn test_set_links() {
         let ancestor = 0usize;
@@ -707,6 +1208,70 @@ mod test {
         assert_eq!(gchild.score, (9000.0 / (3200.0 / 4.0)));
     }
 
+    #[test]
+    fn ancestors_descendants_and_entry_stats_match_cached_totals() {
+        let ancestor_id = 0usize;
+        let parent_id = 1usize;
+        let child_id = 2usize;
+        let gchild_id = 3usize;
+        let mut maker = BlockAssembler::from(
+            vec![
+                entry_with_fee_and_parent(ancestor_id, 1000, None),
+                entry_with_fee_and_parent(parent_id, 2000, Some(ancestor_id)),
+                entry_with_fee_and_parent(child_id, 2000, Some(parent_id)),
+                entry_with_fee_and_parent(gchild_id, 4000, Some(child_id)),
+            ]
+            .into_pool()
+            .1,
+        );
+        maker.set_links(gchild_id);
+
+        assert_eq!(ancestors(&maker.pool, gchild_id), HashSet::from([ancestor_id, parent_id, child_id]));
+        assert_eq!(ancestors(&maker.pool, ancestor_id), HashSet::new());
+        assert_eq!(descendants(&maker.pool, ancestor_id), HashSet::from([parent_id, child_id, gchild_id]));
+        assert_eq!(descendants(&maker.pool, gchild_id), HashSet::new());
+
+        // Independent recomputation agrees with the cached totals `set_links` maintains.
+        let child = maker.pool.get(&child_id).unwrap();
+        let stats = entry_stats(&maker.pool, child_id).unwrap();
+        assert_eq!(stats.ancestor_count, 2);
+        assert_eq!(stats.descendant_count, 1);
+        assert_eq!(stats.package_size, child.ancestor_weight);
+        assert_eq!(stats.package_fee, child.ancestor_fee);
+
+        assert!(entry_stats(&maker.pool, 99).is_none());
+    }
+
+    #[test]
+    fn set_links_caps_score_at_own_feerate() {
+        // parent: high fee, inflates the child's ancestor-package feerate well above
+        // the child's own, lo-fee feerate.
+        let parent = 0usize;
+        let child = 1usize;
+        let standalone = 2usize;
+        let mut maker = BlockAssembler::from(
+            vec![
+                entry_with_fee_and_parent(parent, 5000, None),
+                entry_with_fee_and_parent(child, 400, Some(parent)),
+                entry_with_fee_and_parent(standalone, 400, None),
+            ]
+            .into_pool()
+            .1,
+        );
+        maker.set_links(child);
+        maker.set_links(standalone);
+
+        let child = maker.pool.get(&child).unwrap();
+        let standalone = maker.pool.get(&standalone).unwrap();
+
+        // Own feerate is 400 / (800 / 4) == 2.0; the ancestor-package feerate, which
+        // includes the parent's 5000 sat fee, is 5400 / (1600 / 4) == 13.5. The score
+        // must be capped at the former, matching a standalone tx of equal feerate --
+        // not inflated by a parent that won't still be around once it's mined.
+        assert_eq!(child.score, 2.0);
+        assert_eq!(child.score, standalone.score);
+    }
+
     #[test]
     fn test_update_descendants() {
         let ancestor = 0usize;
@@ -752,4 +1317,214 @@ mod test {
         assert_eq!(uid, child.uid);
         assert_eq!(priority, expect);
     }
+
+    #[test]
+    fn insert_tx_wires_new_descendant_into_existing_ancestor() {
+        let parent = 0usize;
+        let child = 1usize;
+        let mut maker = BlockAssembler::from(vec![entry_with_fee_and_parent(parent, 1000, None)].into_pool().1);
+        maker.set_links(parent);
+
+        let (_, mut new_pool) = vec![entry_with_fee_and_parent(child, 2000, Some(parent))].into_pool();
+        let new_tx = new_pool.remove(&child).unwrap();
+        maker.insert_tx(new_tx);
+
+        let parent_tx = maker.pool.get(&parent).unwrap();
+        assert!(parent_tx.children.contains(&child));
+        let child_tx = maker.pool.get(&child).unwrap();
+        assert!(child_tx.ancestors.contains(&parent));
+        assert_eq!(child_tx.score, 3000.0 / (1600.0 / 4.0));
+    }
+
+    #[test]
+    fn remove_tx_drops_whole_descendant_cone() {
+        let ancestor = 0usize;
+        let parent = 1usize;
+        let child = 2usize;
+        let mut maker = BlockAssembler::from(
+            vec![
+                entry_with_fee_and_parent(ancestor, 1000, None),
+                entry_with_fee_and_parent(parent, 2000, Some(ancestor)),
+                entry_with_fee_and_parent(child, 4000, Some(parent)),
+            ]
+            .into_pool()
+            .1,
+        );
+        maker.set_links(child);
+
+        maker.remove_tx(ancestor);
+
+        // The whole cone (ancestor, parent, child) is gone
+        assert!(maker.pool.is_empty());
+    }
+
+    #[test]
+    fn remove_tx_leaves_sibling_branch_intact() {
+        let parent = 0usize;
+        let child_a = 1usize;
+        let child_b = 2usize;
+        let mut maker = BlockAssembler::from(
+            vec![
+                entry_with_fee_and_parent(parent, 1000, None),
+                entry_with_fee_and_parent(child_a, 2000, Some(parent)),
+                entry_with_fee_and_parent(child_b, 3000, Some(parent)),
+            ]
+            .into_pool()
+            .1,
+        );
+        maker.set_links(child_a);
+        maker.set_links(child_b);
+
+        maker.remove_tx(child_a);
+
+        assert!(!maker.pool.contains_key(&child_a));
+        assert!(maker.pool.contains_key(&child_b));
+        let parent_tx = maker.pool.get(&parent).unwrap();
+        assert!(!parent_tx.children.contains(&child_a));
+        assert!(parent_tx.children.contains(&child_b));
+    }
+
+    #[test]
+    fn estimate_feerates_drives_past_default_block_goal() {
+        // 24 max-weight tx with strictly increasing fee split into three 10/10/4
+        // blocks by weight; targets [1, 2, 3] push block_goal past its default of 2.
+        let mut entries: Vec<TestMempoolEntry> = vec![];
+        for i in 0usize..24 {
+            entries.push(TestMempoolEntry {
+                uid: i,
+                fee: (i as u64 + 1) * 100_000,
+                weight: 396_000,
+                sigops: 0,
+                parents: HashSet::new(),
+            });
+        }
+
+        let estimates = estimate_feerates(entries, &[1, 2, 3]);
+        assert_eq!(estimates.len(), 3);
+        // Later confirmation targets settle for a lower (or equal) minimum feerate
+        assert!(estimates[0].feerate >= estimates[1].feerate);
+        assert!(estimates[1].feerate >= estimates[2].feerate);
+        assert!(estimates.iter().all(|e| e.feerate > 0.0));
+    }
+
+    #[test]
+    fn prioritise_transaction_propagates_to_ancestor_and_descendant() {
+        let parent = 0usize;
+        let child = 1usize;
+        let mut maker = BlockAssembler::from(
+            vec![
+                entry_with_fee_and_parent(parent, 1000, None),
+                entry_with_fee_and_parent(child, 1000, Some(parent)),
+            ]
+            .into_pool()
+            .1,
+        );
+        maker.set_links(child);
+
+        let parent_descendant_fee_before = maker.pool.get(&parent).unwrap().descendant_fee;
+        let child_score_before = maker.pool.get(&child).unwrap().score;
+
+        maker.prioritise_transaction(child, 5000);
+
+        // Ancestor pass: parent's descendant-fee total reflects the bump
+        let parent_tx = maker.pool.get(&parent).unwrap();
+        assert_eq!(parent_tx.descendant_fee, parent_descendant_fee_before + 5000);
+
+        // Descendant pass (inclusive of the prioritised tx itself): score goes up,
+        // and the modified queue is re-heaped so selection order reflects it
+        let child_tx = maker.pool.get(&child).unwrap();
+        assert_eq!(child_tx.fee_delta, 5000);
+        assert!(child_tx.score > child_score_before);
+        assert!(child_tx.modified);
+        assert_eq!(maker.modified.len(), 1);
+        let (uid, _) = maker.modified.peek().unwrap();
+        assert_eq!(*uid, child);
+    }
+
+    #[test]
+    fn iter_by_relay_feerate_ignores_prioritisation() {
+        let lo = 0usize;
+        let hi = 1usize;
+        let mut maker = BlockAssembler::from(
+            vec![
+                entry_with_fee_and_parent(lo, 1000, None),
+                entry_with_fee_and_parent(hi, 2000, None),
+            ]
+            .into_pool()
+            .1,
+        );
+        maker.set_links(lo);
+        maker.set_links(hi);
+
+        // Raw ordering: `hi` relays ahead of `lo`.
+        let order: Vec<usize> = iter_by_relay_feerate(&maker.pool).map(|tx| tx.uid).collect();
+        assert_eq!(order, vec![hi, lo]);
+
+        // A local-only prioritisation bump on `lo` changes its mining score, but must
+        // not be observable through relay ordering.
+        maker.prioritise_transaction(lo, 10_000);
+        assert!(maker.pool.get(&lo).unwrap().score > maker.pool.get(&hi).unwrap().score);
+        let order: Vec<usize> = iter_by_relay_feerate(&maker.pool).map(|tx| tx.uid).collect();
+        assert_eq!(order, vec![hi, lo]);
+    }
+
+    #[test]
+    fn package_is_final_defers_until_lock_height_reached() {
+        let uid = 0usize;
+        let mut pool = vec![entry_with_fee_and_parent(uid, 1000, None)].into_pool().1;
+        pool.get_mut(&uid).unwrap().lock_points = LockPoints {
+            height: 5,
+            time: 0,
+            max_input_block: 0,
+        };
+
+        let early = BlockAssembler::from_pool_with_height(pool.clone(), 4, OrderingStrategy::default());
+        assert!(!early.package_is_final(early.pool.get(&uid).unwrap()));
+
+        let late = BlockAssembler::from_pool_with_height(pool, 5, OrderingStrategy::default());
+        assert!(late.package_is_final(late.pool.get(&uid).unwrap()));
+    }
+
+    #[test]
+    fn generate_defers_non_final_tx_to_a_later_block() {
+        let uid = 0usize;
+        let mut pool = vec![entry_with_fee_and_parent(uid, 1000, None)].into_pool().1;
+        pool.get_mut(&uid).unwrap().lock_points = LockPoints {
+            height: 1,
+            time: 0,
+            max_input_block: 0,
+        };
+
+        let maker = BlockAssembler::from(pool);
+        let blocks = maker.generate();
+
+        // Not final at height 0, so the first block is built empty; once `clear()` has
+        // advanced `next_height` to 1, the tx's lock point is satisfied and it's
+        // collected into the second block instead of being dropped.
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].tx_count, 0);
+        assert_eq!(blocks[1].tx_count, 1);
+        assert!(blocks[1].txn.contains(&uid));
+    }
+
+    #[test]
+    fn generate_recovers_overflow_tx_whose_lock_height_matches_block_goal() {
+        // Lock height is only satisfied once `block_goal` (default 2) empty blocks have
+        // already been produced, so the tx can only land in the final unbounded block.
+        // It must surface there rather than being silently dropped.
+        let uid = 0usize;
+        let mut pool = vec![entry_with_fee_and_parent(uid, 1000, None)].into_pool().1;
+        pool.get_mut(&uid).unwrap().lock_points = LockPoints {
+            height: 2,
+            time: 0,
+            max_input_block: 0,
+        };
+
+        let maker = BlockAssembler::from(pool);
+        let blocks = maker.generate();
+
+        let total_tx: usize = blocks.iter().map(|b| b.tx_count).sum();
+        assert_eq!(total_tx, 1, "tx must not be dropped once block_goal is reached");
+        assert!(blocks.iter().any(|b| b.txn.contains(&uid)));
+    }
 }
@@ -7,6 +7,12 @@ pub struct Args {
     /// Network (bitcoin, testnet, signet, regtest) [default: signet]
     #[clap(long, short = 'n')]
     pub network: Option<String>,
+    /// Bitcoin Core RPC server url, e.g. http://127.0.0.1:8332. Overrides the network default port
+    #[clap(long)]
+    pub rpc_url: Option<String>,
+    /// Path to the bitcoind cookie file used for authentication
+    #[clap(long, env = "RPC_COOKIE")]
+    pub rpc_cookie: Option<String>,
     /// Bitcoin Core RPC user
     #[clap(long, env = "RPC_USER")]
     pub rpc_user: Option<String>,
@@ -47,11 +53,59 @@ pub enum FeeSubCmd {
         /// Check result for correctness, useful for testing
         #[clap(long, short = 'c')]
         check: bool,
+        /// Tx selection strategy: `package-score` (default, consensus-accurate),
+        /// `individual-feerate`, or `entry-order`
+        #[clap(long, default_value = "package-score")]
+        strategy: String,
     },
     /// Tx prioritisation deltas
     Delta,
     /// Mempool cluster analysis
     Cluster,
+    /// Project the next-block base feerate using an EIP-1559-style fee-market controller
+    Predict {
+        /// Number of past confirmed blocks to walk when building the trajectory
+        #[clap(long, short = 'k', default_value_t = 6)]
+        blocks: usize,
+    },
+    /// Report mempool tx at or over Bitcoin Core's ancestor/descendant chain limits
+    Limits {
+        /// Max ancestor/descendant tx count before a chain is considered too large
+        #[clap(long, default_value_t = 25)]
+        max_count: usize,
+        /// Max ancestor/descendant vsize (vB) before a chain is considered too large
+        #[clap(long, default_value_t = 101_000)]
+        max_vsize: u64,
+    },
+    /// Find the nearest in-mempool ancestor shared by two tx, if any
+    CommonAncestor {
+        /// First txid
+        tx_a: String,
+        /// Second txid
+        tx_b: String,
+    },
+    /// Project the next blocks as if a tx (and its descendants) had already been evicted
+    /// from the mempool
+    Exclude {
+        /// Txid to evict before projecting
+        txid: String,
+    },
+    /// Project the next blocks as if a not-yet-broadcast tx had been added to the mempool
+    Inject {
+        /// Raw tx hex
+        hex: String,
+        /// Fee paid by the tx, in satoshis
+        #[clap(long)]
+        fee: u64,
+    },
+    /// Project the next blocks after bumping a tx's mining priority, as in Bitcoin Core's
+    /// `prioritisetransaction`
+    Prioritise {
+        /// Txid to reprioritise
+        txid: String,
+        /// Fee delta to apply, in satoshis (may be negative)
+        fee_delta: i64,
+    },
 }
 
 #[derive(Subcommand, Clone)]
@@ -80,6 +134,30 @@ pub enum AuditSubCmd {
         #[clap(required(true))]
         hash: String,
     },
+    /// Watch an address or script for payments across the mempool and recent blocks
+    Watch {
+        /// Address to watch
+        #[clap(long)]
+        address: Option<String>,
+        /// ScriptPubKey hex to watch
+        #[clap(long)]
+        script: Option<String>,
+    },
+    /// Continuously poll getblocktemplate and score each newly connected block against the
+    /// most recent template preceding it
+    Template {
+        /// Path to append a running export of scored blocks to
+        #[clap(long)]
+        out: Option<String>,
+        /// Export format for --out: `ndjson` or `csv`
+        #[clap(long, default_value = "ndjson")]
+        format: String,
+    },
+    /// List the transitive in-mempool ancestors and descendants of a tx
+    Related {
+        /// Txid
+        txid: String,
+    },
 }
 
 #[derive(Subcommand, Clone)]
@@ -88,6 +166,8 @@ pub enum TaprootSubCmd {
     Outputs(Block),
     /// Scan a block for the "ord" pattern
     Ord(Block),
+    /// Tally BRC-20 events (deploy/mint/transfer) inscribed in a block, grouped by tick
+    Brc20(Block),
 }
 
 /// A required block height
@@ -1,4 +1,5 @@
 use serde::Serialize;
+use std::cmp::Ordering;
 
 use bitcoin::Txid;
 
@@ -22,12 +23,121 @@ pub struct ClusterResult {
     pub ancestors: Vec<Txid>,
     /// Total clusters
     pub count: usize,
+    /// Size (tx count) of each cluster, in descending order
+    pub cluster_sizes: Vec<u32>,
+    /// Package/CPFP feerate summary for each root-ancestor cluster
+    pub cluster_feerates: Vec<ClusterFeerate>,
+}
+
+/// The mining-relevant feerate picture for a single root-ancestor cluster: how
+/// attractive the root looks on its own versus packaged with its best descendant, i.e.
+/// the CPFP uplift a miner would actually use to prioritise the cluster for inclusion.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterFeerate {
+    /// Txid of the cluster's root ancestor
+    pub root: Txid,
+    /// The root's own, unassisted feerate (sat/vB)
+    pub individual_feerate: f64,
+    /// The highest ancestor-score (self + ancestors' fee over vsize) reachable by any
+    /// member of the root's descendant set -- the combined package feerate a miner
+    /// would use to prioritise the cluster
+    pub effective_feerate: f64,
+    /// `effective_feerate - individual_feerate`: the CPFP uplift a low-feerate root
+    /// gets from a high-feerate descendant paying for it
+    pub cpfp_delta: f64,
+}
+
+/// Bitcoin Core's default `-limitancestorcount`/`-limitdescendantcount` (25) and
+/// `-limitancestorsize`/`-limitdescendantsize` (101 KvB, in vbytes) mempool chain limits
+const DEFAULT_CHAIN_LIMIT_COUNT: usize = 25;
+const DEFAULT_CHAIN_LIMIT_VSIZE: u64 = 101_000;
+
+/// Ancestor/descendant chain limits to check the mempool against, analogous to Bitcoin
+/// Core's `-limitancestorcount`/`-limitancestorsize`/`-limitdescendantcount`/
+/// `-limitdescendantsize` settings. Both the ancestor and descendant side share the same
+/// count/vsize thresholds, matching Core's defaults.
+#[derive(Debug, Clone, Copy)]
+pub struct ChainLimits {
+    pub max_count: usize,
+    pub max_vsize: u64,
+}
+
+impl Default for ChainLimits {
+    fn default() -> Self {
+        Self {
+            max_count: DEFAULT_CHAIN_LIMIT_COUNT,
+            max_vsize: DEFAULT_CHAIN_LIMIT_VSIZE,
+        }
+    }
+}
+
+/// A mempool entry that meets or exceeds one or more [`ChainLimits`] thresholds
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainLimitReport {
+    pub txid: Txid,
+    pub ancestor_count: usize,
+    pub ancestor_vsize: u64,
+    pub descendant_count: usize,
+    pub descendant_vsize: u64,
+    /// `max_count - ancestor_count`; negative means the tx is over the ancestor count limit
+    pub ancestor_count_headroom: i64,
+    /// `max_vsize - ancestor_vsize`; negative means the tx is over the ancestor size limit
+    pub ancestor_vsize_headroom: i64,
+    /// `max_count - descendant_count`; negative means the tx is over the descendant count limit
+    pub descendant_count_headroom: i64,
+    /// `max_vsize - descendant_vsize`; negative means the tx is over the descendant size limit
+    pub descendant_vsize_headroom: i64,
+}
+
+/// A minimal disjoint-set (union-find) over the pool's uid space, indexed directly by
+/// uid since pool entries are assigned dense, contiguous ids. Path-compresses on `find`
+/// and unions by rank. Used to compute Bitcoin's cluster-mempool notion of a cluster --
+/// the full connected component of transactions joined by any in-mempool parent/child
+/// relationship -- rather than naively counting root ancestors, which mis-counts a
+/// diamond (two independent ancestors sharing a descendant) as two clusters.
+struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+        }
+    }
+
+    fn find(&mut self, uid: usize) -> usize {
+        if self.parent[uid] != uid {
+            self.parent[uid] = self.find(self.parent[uid]);
+        }
+        self.parent[uid]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let ra = self.find(a);
+        let rb = self.find(b);
+        if ra == rb {
+            return;
+        }
+        match self.rank[ra].cmp(&self.rank[rb]) {
+            Ordering::Less => self.parent[ra] = rb,
+            Ordering::Greater => self.parent[rb] = ra,
+            Ordering::Equal => {
+                self.parent[rb] = ra;
+                self.rank[ra] += 1;
+            }
+        }
+    }
 }
 
 /// A stripped-down mempool entry suitable for cluster analysis
 #[derive(Debug, Default)]
 struct Entry {
     uid: usize,
+    fee: u64,
+    vsize: u64,
     children: HashSet<usize>,
     parents: HashSet<usize>,
     ancestors: HashSet<usize>,
@@ -78,16 +188,109 @@ pub fn analyze(
         .collect();
 
     let depth = auditor.max_descendant_depth();
-    let count = auditor.cluster_count();
+    let cluster_sizes = auditor.cluster_sizes();
+    let count = cluster_sizes.len();
+
+    let cluster_feerates = auditor
+        .cluster_feerates()
+        .into_iter()
+        .map(|(uid, individual_feerate, effective_feerate)| ClusterFeerate {
+            root: util::try_from_value(&index, &uid).expect("uid exist"),
+            individual_feerate,
+            effective_feerate,
+            cpfp_delta: effective_feerate - individual_feerate,
+        })
+        .collect();
 
     ClusterResult {
         depth,
         size,
         ancestors,
         count,
+        cluster_sizes,
+        cluster_feerates,
     }
 }
 
+/* Called from main */
+/// Reports every mempool entry that meets or exceeds one or more of the given
+/// `limits`, along with its headroom on each metric, so a caller can see which tx are
+/// at risk of being rejected for forming too large an ancestor/descendant chain.
+pub fn find_chain_limit_violations(
+    entries: HashMap<Txid, bitcoincore_rpc_json::GetMempoolEntryResult>,
+    limits: ChainLimits,
+) -> Vec<ChainLimitReport> {
+    let index = util::key_index(&entries);
+    let pool = pool_from_entries_with_index(entries, &index);
+    let mut auditor = Auditor::from(pool);
+
+    for uid in 0..auditor.pool.len() {
+        auditor.set_links(uid);
+    }
+
+    let uids: Vec<usize> = auditor.pool.keys().copied().collect();
+    uids.into_iter()
+        .filter_map(|uid| {
+            let tx = auditor.pool.get(&uid).expect("uid exists");
+            // Core counts the tx itself as part of its own ancestor/descendant chain
+            let ancestor_count = tx.ancestors.len() + 1;
+            let ancestor_vsize = tx.vsize
+                + tx.ancestors
+                    .iter()
+                    .filter_map(|id| auditor.pool.get(id))
+                    .map(|a| a.vsize)
+                    .sum::<u64>();
+            let descendant_count = auditor.descendant_count(uid) + 1;
+            let descendant_vsize = tx.vsize + auditor.descendant_vsize(uid);
+
+            let over_limit = ancestor_count >= limits.max_count
+                || ancestor_vsize >= limits.max_vsize
+                || descendant_count >= limits.max_count
+                || descendant_vsize >= limits.max_vsize;
+            if !over_limit {
+                return None;
+            }
+
+            let txid = util::try_from_value(&index, &uid).expect("uid exists");
+            Some(ChainLimitReport {
+                txid,
+                ancestor_count,
+                ancestor_vsize,
+                descendant_count,
+                descendant_vsize,
+                ancestor_count_headroom: limits.max_count as i64 - ancestor_count as i64,
+                ancestor_vsize_headroom: limits.max_vsize as i64 - ancestor_vsize as i64,
+                descendant_count_headroom: limits.max_count as i64 - descendant_count as i64,
+                descendant_vsize_headroom: limits.max_vsize as i64 - descendant_vsize as i64,
+            })
+        })
+        .collect()
+}
+
+/* Called from main */
+/// Finds the lowest (nearest) in-mempool ancestor shared by `txid_a` and `txid_b`, so a
+/// caller can confirm the two belong to the same unconfirmable package and identify the
+/// single root tx whose confirmation would unblock both. Returns `None` if either txid
+/// is unknown to `entries`, or if the two share no common ancestor (disjoint clusters).
+pub fn find_common_ancestor(
+    entries: HashMap<Txid, bitcoincore_rpc_json::GetMempoolEntryResult>,
+    txid_a: Txid,
+    txid_b: Txid,
+) -> Option<Txid> {
+    let index = util::key_index(&entries);
+    let pool = pool_from_entries_with_index(entries, &index);
+    let mut auditor = Auditor::from(pool);
+
+    for uid in 0..auditor.pool.len() {
+        auditor.set_links(uid);
+    }
+
+    let uid_a = *index.get(&txid_a)?;
+    let uid_b = *index.get(&txid_b)?;
+    let lca = auditor.lowest_common_ancestor(uid_a, uid_b)?;
+    util::try_from_value(&index, &lca)
+}
+
 impl Auditor {
     /// Returns the total descendant count of the largest cluster, along with
     /// the uid (or uids in the case of a tie) corresponding to the root
@@ -134,35 +337,80 @@ impl Auditor {
         heights.pop().expect("collected tree heights is not empty")
     }
 
-    /// Computes height of a tree given a root node, based on an algorithm for finding
-    /// the height of a binary tree, generalized for nodes with many children.
+    /// Computes height of a tree given a root node, via an iterative post-order
+    /// traversal with a memo, so a node reachable by more than one path (as in a
+    /// diamond-shaped cluster) has its height computed once rather than once per path.
     fn tree_height(&self, tx: &Entry) -> u32 {
-        if tx.children.is_empty() {
-            return 0;
+        let mut memo: HashMap<usize, u32> = HashMap::new();
+        // (uid, children already pushed for processing)
+        let mut stack: Vec<(usize, bool)> = vec![(tx.uid, false)];
+
+        while let Some((uid, expanded)) = stack.pop() {
+            if memo.contains_key(&uid) {
+                continue;
+            }
+            let node = self.pool.get(&uid).expect("uid exists");
+            if node.children.is_empty() {
+                memo.insert(uid, 0);
+            } else if expanded {
+                let height = node
+                    .children
+                    .iter()
+                    .map(|child| *memo.get(child).expect("child height computed"))
+                    .max()
+                    .expect("children not empty")
+                    + 1;
+                memo.insert(uid, height);
+            } else {
+                stack.push((uid, true));
+                for &child in &node.children {
+                    if !memo.contains_key(&child) {
+                        stack.push((child, false));
+                    }
+                }
+            }
         }
 
-        let mut heights: Vec<u32> = vec![];
-        for child in &tx.children {
-            let tx = self.pool.get(child).expect("uid exists");
-            /* recursive step */
-            heights.push(self.tree_height(tx));
+        *memo.get(&tx.uid).expect("root height computed")
+    }
+
+    /// Builds a disjoint-set over this pool, unioning each entry with every one of its
+    /// direct parents so the resulting components are Bitcoin's notion of a cluster:
+    /// the full connected component joined by any parent/child relationship, not just
+    /// the descendants of a single root ancestor.
+    fn disjoint_set(&self) -> DisjointSet {
+        let mut dsu = DisjointSet::new(self.pool.len());
+        for tx in self.pool.values() {
+            for &parent_id in &tx.parents {
+                dsu.union(tx.uid, parent_id);
+            }
         }
+        dsu
+    }
 
-        heights.sort_unstable();
-        heights.pop().expect("children not empty") + 1
+    /// Returns the size (tx count) of each connected-component cluster with more than
+    /// one member, in descending order. A lone, unrelated mempool entry isn't
+    /// considered a cluster.
+    fn cluster_sizes(&self) -> Vec<u32> {
+        let mut dsu = self.disjoint_set();
+        let mut sizes: HashMap<usize, u32> = HashMap::new();
+        for uid in 0..self.pool.len() {
+            let root = dsu.find(uid);
+            *sizes.entry(root).or_insert(0) += 1;
+        }
+
+        let mut sizes: Vec<u32> = sizes.into_values().filter(|&n| n > 1).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
     }
 
-    /// Counts the number of mempool clusters
+    /// Counts the number of mempool clusters, where a cluster is the full connected
+    /// component of transactions joined by any in-mempool parent/child relationship.
+    /// Unlike naively counting root ancestors (entries with children and no ancestors),
+    /// this correctly reports a diamond-shaped cluster (two independent ancestors
+    /// sharing a descendant) as a single cluster.
     fn cluster_count(&self) -> usize {
-        // count defined as number of tx having at least one child
-        // and having no ancestors
-        let ancestors: Vec<&Entry> = self
-            .pool
-            .values()
-            .filter(|tx| tx.ancestors.is_empty() && !tx.children.is_empty())
-            .collect();
-
-        ancestors.len()
+        self.cluster_sizes().len()
     }
 
     /// Counts the number of descendants of the given `tx` entry
@@ -181,42 +429,172 @@ impl Auditor {
         }
         ct
     }
+
+    /// Returns the unique set of in-mempool descendant uids for `uid`, via DFS over
+    /// `children`. Dedups through a `HashSet` since the mempool is a DAG, not a tree, so
+    /// naively summing child counts (as the deprecated `count_descendants` does)
+    /// over-counts any descendant reachable through more than one path.
+    fn descendant_set(&self, uid: usize) -> HashSet<usize> {
+        let mut seen = HashSet::new();
+        let mut queue: Vec<usize> = self
+            .pool
+            .get(&uid)
+            .map(|tx| tx.children.iter().copied().collect())
+            .unwrap_or_default();
+        while let Some(next) = queue.pop() {
+            if seen.insert(next) {
+                if let Some(tx) = self.pool.get(&next) {
+                    queue.extend(tx.children.iter().copied());
+                }
+            }
+        }
+        seen
+    }
+
+    /// Number of unique in-mempool descendants of `uid`, deduped per [`Self::descendant_set`]
+    fn descendant_count(&self, uid: usize) -> usize {
+        self.descendant_set(uid).len()
+    }
+
+    /// Cumulative vsize of `uid`'s unique in-mempool descendants, deduped per
+    /// [`Self::descendant_set`]
+    fn descendant_vsize(&self, uid: usize) -> u64 {
+        self.descendant_set(uid)
+            .iter()
+            .filter_map(|id| self.pool.get(id))
+            .map(|tx| tx.vsize)
+            .sum()
+    }
+
+    /// The ancestor score of `uid`: the combined fee over combined vsize of `uid` and
+    /// all of its in-mempool ancestors, i.e. the package feerate a miner sees when
+    /// considering `uid` for inclusion.
+    fn ancestor_score(&self, uid: usize) -> f64 {
+        let Some(tx) = self.pool.get(&uid) else {
+            return 0.0;
+        };
+        let fee = tx.fee
+            + tx.ancestors
+                .iter()
+                .filter_map(|id| self.pool.get(id))
+                .map(|a| a.fee)
+                .sum::<u64>();
+        let vsize = tx.vsize
+            + tx.ancestors
+                .iter()
+                .filter_map(|id| self.pool.get(id))
+                .map(|a| a.vsize)
+                .sum::<u64>();
+        if vsize == 0 {
+            0.0
+        } else {
+            fee as f64 / vsize as f64
+        }
+    }
+
+    /// For each root ancestor, returns `(uid, individual_feerate, effective_feerate)`:
+    /// the root's own unassisted feerate, and the highest ancestor-score reachable by
+    /// any member of its descendant set -- the combined package feerate a miner would
+    /// actually use to prioritise the cluster, capturing any CPFP uplift from a
+    /// high-feerate child.
+    fn cluster_feerates(&self) -> Vec<(usize, f64, f64)> {
+        let Some(roots) = self.ancestors() else {
+            return Vec::new();
+        };
+        roots
+            .into_iter()
+            .map(|root| {
+                let individual_feerate = self.ancestor_score(root.uid);
+                let effective_feerate = self
+                    .descendant_set(root.uid)
+                    .into_iter()
+                    .map(|uid| self.ancestor_score(uid))
+                    .fold(individual_feerate, f64::max);
+                (root.uid, individual_feerate, effective_feerate)
+            })
+            .collect()
+    }
+
+    /// Finds the lowest (nearest) in-mempool ancestor shared by `a` and `b`, or `None`
+    /// if they belong to disjoint clusters. Intersects `a` and `b`'s own ancestor sets
+    /// (each including itself, so one being a direct ancestor of the other is handled)
+    /// built by [`Cluster::set_links`], then picks the intersection member with the
+    /// largest ancestor set -- the one that is itself a descendant of every other
+    /// candidate, i.e. the nearest shared ancestor rather than some higher one.
+    fn lowest_common_ancestor(&self, a: usize, b: usize) -> Option<usize> {
+        let tx_a = self.pool.get(&a)?;
+        let tx_b = self.pool.get(&b)?;
+        let set_a: HashSet<usize> = tx_a.ancestors.iter().copied().chain([a]).collect();
+        let set_b: HashSet<usize> = tx_b.ancestors.iter().copied().chain([b]).collect();
+
+        set_a
+            .intersection(&set_b)
+            .max_by_key(|&&uid| self.pool.get(&uid).map_or(0, |tx| tx.ancestors.len()))
+            .copied()
+    }
 }
 
 impl Cluster for Auditor {
+    /// Resolves ancestor sets and wires up `children` for every not-yet-linked entry in
+    /// the pool via an iterative topological sort (Kahn's algorithm) over the parent
+    /// edges, rather than descending into parents recursively. A diamond-shaped
+    /// cluster's shared ancestor is visited once instead of once per path to it, and a
+    /// long chain can't blow the stack. `uid` is kept for interface parity with
+    /// [`Cluster`]; since one call resolves the whole pool in a linear pass, the first
+    /// unlinked call does the work and the rest become no-ops via `links_set`.
     fn set_links(&mut self, uid: usize) {
-        let tx = self.pool.get(&uid).expect("uid exists");
-        if tx.links_set {
+        if self.pool.get(&uid).map_or(true, |tx| tx.links_set) {
             return;
         }
 
-        // get this tx's parents
-        // (clone to avoid holding the borrow in the next step)
-        let parents = tx.parents.clone();
-
-        // get ancestor uid's for this tx
-        let mut ancestors: HashSet<usize> = HashSet::new();
-        for parent_id in parents {
-            /* recursive step */
-            self.set_links(parent_id);
-            let parent = self.pool.get_mut(&parent_id).expect("uid exists");
-
-            // add the current uid to parent's children
-            parent.children.insert(uid);
+        // in-degree = count of not-yet-resolved parents; `dependents` is the reverse
+        // edge list, since a resolved parent needs to know who's waiting on it.
+        let mut in_degree: HashMap<usize, usize> = HashMap::new();
+        let mut dependents: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut ready: Vec<usize> = vec![];
+        for tx in self.pool.values() {
+            if tx.links_set {
+                continue;
+            }
+            let unresolved = tx
+                .parents
+                .iter()
+                .filter(|parent_id| !self.pool.get(parent_id).map_or(false, |p| p.links_set))
+                .count();
+            in_degree.insert(tx.uid, unresolved);
+            if unresolved == 0 {
+                ready.push(tx.uid);
+            }
+            for &parent_id in &tx.parents {
+                dependents.entry(parent_id).or_default().push(tx.uid);
+            }
+        }
 
-            // include this parent as an ancestor
-            ancestors.insert(parent.uid);
+        while let Some(id) = ready.pop() {
+            let parents = self.pool.get(&id).expect("uid exists").parents.clone();
+            let mut ancestors: HashSet<usize> = HashSet::new();
+            for parent_id in &parents {
+                let parent = self.pool.get_mut(parent_id).expect("uid exists");
+                parent.children.insert(id);
+                ancestors.insert(*parent_id);
+                ancestors.extend(parent.ancestors.iter().copied());
+            }
 
-            // collect parent's ancestors
-            for ancestor_id in &parent.ancestors {
-                ancestors.insert(*ancestor_id);
+            let tx = self.pool.get_mut(&id).expect("uid exists");
+            tx.ancestors = ancestors;
+            tx.links_set = true;
+
+            if let Some(deps) = dependents.get(&id) {
+                for &dep in deps {
+                    if let Some(count) = in_degree.get_mut(&dep) {
+                        *count -= 1;
+                        if *count == 0 {
+                            ready.push(dep);
+                        }
+                    }
+                }
             }
         }
-
-        // set this tx's ancestors
-        let tx = self.pool.get_mut(&uid).expect("uid exists");
-        tx.ancestors = ancestors;
-        tx.links_set = true;
     }
 }
 
@@ -237,6 +615,8 @@ fn pool_from_entries_with_index(
 
             let entry = Entry {
                 uid: *uid,
+                fee: mempool_entry.fees.modified.to_sat(),
+                vsize: mempool_entry.vsize,
                 parents,
                 ..Default::default()
             };
@@ -264,6 +644,14 @@ mod test {
         }
     }
 
+    fn entry_with_parents(uid: usize, parents: Vec<usize>) -> Entry {
+        Entry {
+            uid,
+            parents: parents.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
     fn pool_from(entries: Vec<Entry>) -> HashMap<usize, Entry> {
         entries
             .into_iter()
@@ -320,4 +708,120 @@ mod test {
         // cluster count
         assert_eq!(auditor.cluster_count(), 2);
     }
+
+    #[test]
+    fn diamond_shaped_cluster_counts_as_one() {
+        // Two independent ancestors (0, 1) share a descendant (2): a CPFP batch or
+        // merge-spend. Counting roots naively sees two entries with children and no
+        // ancestors, reporting two clusters; the true connected component is one.
+        let ancestor0 = 0usize;
+        let ancestor1 = 1usize;
+        let child = 2usize;
+
+        let entries = vec![
+            entry_with_parents(ancestor0, vec![]),
+            entry_with_parents(ancestor1, vec![]),
+            entry_with_parents(child, vec![ancestor0, ancestor1]),
+        ];
+        let pool = pool_from(entries);
+        let mut auditor = Auditor::from(pool);
+        for uid in 0..3 {
+            auditor.set_links(uid);
+        }
+
+        assert_eq!(auditor.cluster_count(), 1);
+        assert_eq!(auditor.cluster_sizes(), vec![3]);
+    }
+
+    #[test]
+    fn descendant_set_dedups_diamond_shaped_descendants() {
+        // ancestor -> {left, right} -> grandchild: grandchild is reachable via two
+        // paths, so a naive sum would count it (and its vsize) twice.
+        let ancestor = Entry {
+            uid: 0,
+            vsize: 500,
+            ..Default::default()
+        };
+        let left = Entry {
+            uid: 1,
+            vsize: 500,
+            parents: HashSet::from([0]),
+            ..Default::default()
+        };
+        let right = Entry {
+            uid: 2,
+            vsize: 500,
+            parents: HashSet::from([0]),
+            ..Default::default()
+        };
+        let grandchild = Entry {
+            uid: 3,
+            vsize: 500,
+            parents: HashSet::from([1, 2]),
+            ..Default::default()
+        };
+        let pool = pool_from(vec![ancestor, left, right, grandchild]);
+        let mut auditor = Auditor::from(pool);
+        for uid in 0..4 {
+            auditor.set_links(uid);
+        }
+
+        assert_eq!(auditor.descendant_count(0), 3);
+        assert_eq!(auditor.descendant_vsize(0), 1_500);
+    }
+
+    #[test]
+    fn cluster_feerates_reflects_cpfp_uplift_from_child() {
+        // parent pays 1 sat/vB alone (200 sat / 200 vB); its child pays a much
+        // higher 2000 sat / 200 vB (10 sat/vB), i.e. a 2200 sat / 400 vB == 5.5 sat/vB
+        // combined package rate.
+        let parent = Entry {
+            uid: 0,
+            fee: 200,
+            vsize: 200,
+            ..Default::default()
+        };
+        let child = Entry {
+            uid: 1,
+            fee: 2_000,
+            vsize: 200,
+            parents: HashSet::from([0]),
+            ..Default::default()
+        };
+        let pool = pool_from(vec![parent, child]);
+        let mut auditor = Auditor::from(pool);
+        for uid in 0..2 {
+            auditor.set_links(uid);
+        }
+
+        let feerates = auditor.cluster_feerates();
+        assert_eq!(feerates.len(), 1);
+        let (root, individual_feerate, effective_feerate) = feerates[0];
+        assert_eq!(root, 0);
+        assert_eq!(individual_feerate, 1.0);
+        assert_eq!(effective_feerate, 5.5);
+    }
+
+    #[test]
+    fn lowest_common_ancestor_picks_nearest_shared_ancestor() {
+        // root -> mid -> {a, b}: the nearest shared ancestor of a and b is mid, not
+        // root, even though root is also a common ancestor.
+        let root = entry_with_parents(0, vec![]);
+        let mid = entry_with_parents(1, vec![0]);
+        let a = entry_with_parents(2, vec![1]);
+        let b = entry_with_parents(3, vec![1]);
+        let unrelated = entry_with_parents(4, vec![]);
+
+        let pool = pool_from(vec![root, mid, a, b, unrelated]);
+        let mut auditor = Auditor::from(pool);
+        for uid in 0..5 {
+            auditor.set_links(uid);
+        }
+
+        assert_eq!(auditor.lowest_common_ancestor(2, 3), Some(1));
+        // a direct ancestor of b counts as their own common ancestor
+        assert_eq!(auditor.lowest_common_ancestor(1, 2), Some(1));
+        // disjoint clusters share no ancestor
+        assert_eq!(auditor.lowest_common_ancestor(2, 4), None);
+    }
 }
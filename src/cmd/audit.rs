@@ -11,7 +11,11 @@ use bitcoin::BlockHash;
 use bitcoin::hashes::sha256d;
 use bitcoin::Transaction;
 use bitcoin::Txid;
+use mempool::blockmk;
 use mempool::sigops;
+use mempool::template::TemplateTracker;
+use mempool::watch::Watcher;
+use mempool::Audit;
 use mempool::SUBSIDY;
 
 use super::*;
@@ -157,6 +161,91 @@ pub fn execute(core: &Client, subcmd: AuditSubCmd) -> Result<()> {
             };
             log::info!("{}", serde_json::to_string(&obj)?);
         },
+        // Watch an address or script for payments
+        AuditSubCmd::Watch { address, script } => {
+            let spk = if let Some(script) = script {
+                bitcoin::ScriptBuf::from_hex(&script)?
+            } else if let Some(address) = address {
+                bitcoin::Address::from_str(&address)?
+                    .assume_checked()
+                    .script_pubkey()
+            } else {
+                anyhow::bail!("must provide one of --address or --script");
+            };
+
+            let mut scripts = std::collections::HashSet::new();
+            scripts.insert(spk);
+            let mut watcher = Watcher::new(scripts);
+
+            loop {
+                let changed = watcher.poll(core)?;
+                for result in changed {
+                    println!("{}", serde_json::to_string(&result)?);
+                }
+                thread::sleep(Duration::from_secs(30));
+            }
+        },
+        // Continuously poll getblocktemplate and score newly connected blocks against it
+        AuditSubCmd::Template { out, format } => {
+            let export_format = match format.as_str() {
+                "csv" => mempool::report::ExportFormat::Csv,
+                _ => mempool::report::ExportFormat::Ndjson,
+            };
+
+            let mut tracker = TemplateTracker::new();
+            let mut last_height = core.get_block_count()?;
+
+            loop {
+                tracker.poll(core)?;
+
+                let height = core.get_block_count()?;
+                if height > last_height {
+                    let hash = core.get_block_hash(height)?;
+                    let block = core.get_block(&hash)?;
+
+                    if let Some(report) = tracker.report_connected(height, &block) {
+                        log::info!("{}", serde_json::to_string(&report)?);
+
+                        if let Some(path) = &out {
+                            let file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+                            mempool::report::export(&[report], export_format, file)?;
+                        }
+                    }
+                    last_height = height;
+                }
+
+                thread::sleep(Duration::from_secs(30));
+            }
+        },
+        // List the transitive in-mempool ancestors and descendants of a tx
+        AuditSubCmd::Related { txid } => {
+            let txid: Txid = txid.parse()?;
+
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+            let (index, pool) = raw_mempool.into_pool();
+
+            let Some(&uid) = index.get(&txid) else {
+                println!("tx not found in mempool");
+                return Ok(());
+            };
+            let txid_of: std::collections::HashMap<usize, Txid> =
+                index.into_iter().map(|(txid, uid)| (uid, txid)).collect();
+
+            let ancestors: Vec<Txid> = blockmk::ancestors(&pool, uid)
+                .into_iter()
+                .filter_map(|uid| txid_of.get(&uid).copied())
+                .collect();
+            let descendants: Vec<Txid> = blockmk::descendants(&pool, uid)
+                .into_iter()
+                .filter_map(|uid| txid_of.get(&uid).copied())
+                .collect();
+
+            let res = serde_json::json!({
+                "ancestors": ancestors,
+                "descendants": descendants,
+            });
+            println!("{}", serde_json::to_string_pretty(&res)?);
+        },
     }
 
     Ok(())
@@ -9,6 +9,15 @@ use mempool::truncate;
 use super::*;
 use crate::cli::FeeSubCmd;
 
+/// Half the consensus block weight limit, the analog of an EIP-1559 gas target
+const WEIGHT_TARGET: f64 = 2_000_000.0;
+/// Denominator of the base-fee adjustment rule (1/8 per block)
+const ADJUSTMENT_DENOM: f64 = 8.0;
+/// Maximum fractional change in base feerate allowed per block
+const MAX_STEP_PCT: f64 = 0.125;
+/// Minimum relay feerate (sat/vB)
+const RELAY_MIN_FEERATE: f64 = 1.0;
+
 /// Format for logging fee report result json
 #[derive(Debug, Serialize)]
 struct FeeReportResult {
@@ -17,11 +26,30 @@ struct FeeReportResult {
     height: u64,
 }
 
+/// One entry in a projected base-feerate trajectory
+#[derive(Debug, Serialize)]
+struct FeePrediction {
+    /// Confirmed block height. `None` denotes the final, unconfirmed projection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u64>,
+    /// Weight consumed by the block. `None` denotes the final, unconfirmed projection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight_used: Option<u64>,
+    /// Projected minimum base feerate (sat/vB)
+    base_feerate: f64,
+}
+
 /// Get network fee statistics
 pub fn execute(core: &Client, subcmd: FeeSubCmd) -> Result<()> {
     match subcmd {
         // Collect fee data from mempool
-        FeeSubCmd::Report { quiet, check } => {
+        FeeSubCmd::Report { quiet, check, strategy } => {
+            let strategy = match strategy.as_str() {
+                "individual-feerate" => blockmk::OrderingStrategy::ByIndividualFeerate,
+                "entry-order" => blockmk::OrderingStrategy::ByEntryOrder,
+                _ => blockmk::OrderingStrategy::ByPackageScore,
+            };
+
             // get raw mempool
             let height = core.get_block_count()?;
             let next_height = height + 1;
@@ -33,7 +61,12 @@ pub fn execute(core: &Client, subcmd: FeeSubCmd) -> Result<()> {
 
             // generate blocks, validate result
             let raw_mempool_count = raw_mempool.len();
-            let blocks = blockmk::audit_fees(next_height, raw_mempool);
+            let blocks = blockmk::audit_fees_with_sigops_and_strategy(
+                next_height,
+                raw_mempool,
+                core,
+                strategy,
+            )?;
             if check {
                 validate_result(raw_mempool_count, &blocks);
             }
@@ -145,6 +178,138 @@ pub fn execute(core: &Client, subcmd: FeeSubCmd) -> Result<()> {
             let res = cluster::analyze(raw_mempool);
             println!("{}", serde_json::to_string_pretty(&res)?);
         },
+        FeeSubCmd::Limits { max_count, max_vsize } => {
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+            if raw_mempool.is_empty() {
+                println!("mempool empty");
+                return Ok(());
+            }
+
+            let limits = cluster::ChainLimits { max_count, max_vsize };
+            let res = cluster::find_chain_limit_violations(raw_mempool, limits);
+            println!("{}", serde_json::to_string_pretty(&res)?);
+        },
+        FeeSubCmd::CommonAncestor { tx_a, tx_b } => {
+            let txid_a: bitcoin::Txid = tx_a.parse().expect("parse txid");
+            let txid_b: bitcoin::Txid = tx_b.parse().expect("parse txid");
+
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+            if raw_mempool.is_empty() {
+                println!("mempool empty");
+                return Ok(());
+            }
+
+            match cluster::find_common_ancestor(raw_mempool, txid_a, txid_b) {
+                Some(txid) => println!("Common ancestor: {txid}"),
+                None => println!("No common ancestor"),
+            }
+        },
+        // Project as if a tx had already been evicted from the mempool
+        FeeSubCmd::Exclude { txid } => {
+            let txid: bitcoin::Txid = txid.parse()?;
+
+            let height = core.get_block_count()?;
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+            if raw_mempool.is_empty() {
+                println!("mempool empty");
+                return Ok(());
+            }
+
+            let blocks = blockmk::audit_fees_excluding(
+                height + 1,
+                raw_mempool,
+                blockmk::OrderingStrategy::default(),
+                txid,
+            );
+            println!("{}", serde_json::to_string_pretty(&blocks)?);
+        },
+        // Project as if a not-yet-broadcast tx had been added to the mempool
+        FeeSubCmd::Inject { hex, fee } => {
+            use bitcoin::consensus::encode::deserialize_hex;
+
+            let tx: bitcoin::Transaction = deserialize_hex(&hex)?;
+            let weight = tx.weight().to_wu();
+
+            let height = core.get_block_count()?;
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+
+            let injected = mempool::audittx::AuditTx {
+                fee,
+                weight,
+                ..Default::default()
+            };
+            let blocks = blockmk::audit_fees_including(
+                height + 1,
+                raw_mempool,
+                blockmk::OrderingStrategy::default(),
+                injected,
+            );
+            println!("{}", serde_json::to_string_pretty(&blocks)?);
+        },
+        // Project as if a tx's mining priority had been bumped
+        FeeSubCmd::Prioritise { txid, fee_delta } => {
+            let txid: bitcoin::Txid = txid.parse()?;
+
+            let height = core.get_block_count()?;
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+            if raw_mempool.is_empty() {
+                println!("mempool empty");
+                return Ok(());
+            }
+
+            let blocks = blockmk::audit_fees_with_priority(
+                height + 1,
+                raw_mempool,
+                blockmk::OrderingStrategy::default(),
+                txid,
+                fee_delta,
+            );
+            println!("{}", serde_json::to_string_pretty(&blocks)?);
+        },
+        // Project next-block base feerate trajectory
+        FeeSubCmd::Predict { blocks: k } => {
+            let tip = core.get_block_count()?;
+
+            // Seed the trajectory from the fee-cutoff of the currently projected next block
+            let raw_mempool = core.get_raw_mempool_verbose()?;
+            let seed = if raw_mempool.is_empty() {
+                RELAY_MIN_FEERATE
+            } else {
+                blockmk::audit_fees(tip + 1, raw_mempool)
+                    .first()
+                    .map(|b| b.fee_range.0)
+                    .unwrap_or(RELAY_MIN_FEERATE)
+            };
+
+            let mut base = seed;
+            let mut trajectory = Vec::with_capacity(k + 1);
+
+            let start = tip.saturating_sub(k as u64 - 1);
+            for height in start..=tip {
+                let hash = core.get_block_hash(height)?;
+                let block = core.get_block(&hash)?;
+                let weight_used = block.weight().to_wu() as f64;
+
+                trajectory.push(FeePrediction {
+                    height: Some(height),
+                    weight_used: Some(weight_used as u64),
+                    base_feerate: truncate!(base),
+                });
+
+                let delta = (1.0 / ADJUSTMENT_DENOM) * (weight_used - WEIGHT_TARGET) / WEIGHT_TARGET;
+                let delta = delta.clamp(-MAX_STEP_PCT, MAX_STEP_PCT);
+                base = (base * (1.0 + delta)).max(RELAY_MIN_FEERATE);
+            }
+
+            // Final, unconfirmed projection for the next block
+            trajectory.push(FeePrediction {
+                height: None,
+                weight_used: None,
+                base_feerate: truncate!(base),
+            });
+
+            println!("{}", serde_json::to_string_pretty(&trajectory)?);
+        },
     }
     Ok(())
 }
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use bitcoincore_rpc::Client;
 use bitcoincore_rpc::RpcApi;
+use mempool::script::{self, ScriptType};
+use bitcoin::Network;
 
 pub mod audit;
 pub mod fee;
@@ -16,9 +18,20 @@ pub fn hash(core: &Client) -> Result<()> {
     Ok(())
 }
 
-/// Convert bitcoin script hex to asm string
-pub fn parse_script(s: &str) -> Result<()> {
+/// Convert bitcoin script hex to asm string, classify its output type, and print the
+/// corresponding address on `network`, if one exists
+pub fn parse_script(s: &str, network: Network) -> Result<()> {
     let script = bitcoin::ScriptBuf::from_hex(s)?;
     println!("{}", script.to_asm_string());
+
+    let classified = script::classify(&script, network);
+    match classified.script_type {
+        ScriptType::Multisig { m, n } => println!("type: {m}-of-{n} multisig"),
+        script_type => println!("type: {script_type:?}"),
+    }
+    if let Some(address) = classified.address {
+        println!("address: {address}");
+    }
+
     Ok(())
 }
@@ -31,6 +31,18 @@ pub fn execute(core: &Client, subcmd: TaprootSubCmd) -> Result<()> {
                 taproot::tr_ord_count(block)
             );
         }
+        // Tally BRC-20 events
+        TaprootSubCmd::Brc20(block) => {
+            let height = if block.height.is_none() {
+                core.get_block_count()?
+            } else {
+                block.height.unwrap()
+            };
+            let hash = core.get_block_hash(height)?;
+            let block = core.get_block(&hash)?;
+            let tally = taproot::brc20_tally(&block);
+            println!("{}", serde_json::to_string_pretty(&tally)?);
+        }
     }
 
     Ok(())
@@ -5,6 +5,12 @@ use std::fmt;
 pub enum Error {
     /// bitcoind RPC error
     CoreRpc(bitcoincore_rpc::Error),
+    /// I/O error, e.g. while exporting a report
+    Io(std::io::Error),
+    /// JSON (de)serialization error
+    Json(serde_json::Error),
+    /// CSV serialization error
+    Csv(csv::Error),
 }
 
 impl From<bitcoincore_rpc::Error> for Error {
@@ -13,10 +19,31 @@ impl From<bitcoincore_rpc::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
             Self::CoreRpc(e) => e.to_string(),
+            Self::Io(e) => e.to_string(),
+            Self::Json(e) => e.to_string(),
+            Self::Csv(e) => e.to_string(),
         };
         f.write_str(&s)
     }
@@ -0,0 +1,334 @@
+use bitcoin::hashes::Hash;
+use bitcoin::{Block, BlockHash, ScriptBuf};
+
+/// Golomb-Rice coding parameter, per BIP158
+const P: u32 = 19;
+/// Target false-positive rate denominator, per BIP158
+const M: u64 = 784_931;
+
+/// Builds a BIP158 basic block filter over every scriptPubKey paid to in `block`.
+///
+/// Note: a fully faithful "basic filter" also commits to the scriptPubKeys *spent*
+/// by the block's inputs, which requires a prevout lookup this function doesn't have
+/// access to. This builds the filter over output scripts only.
+pub fn build_filter(block: &Block) -> Vec<u8> {
+    let (k0, k1) = filter_key(&block.block_hash());
+
+    let elements: Vec<&[u8]> = block
+        .txdata
+        .iter()
+        .flat_map(|tx| tx.output.iter())
+        .map(|txo| txo.script_pubkey.as_bytes())
+        .collect();
+
+    encode_filter(&elements, k0, k1)
+}
+
+/// Whether any of the given `targets` are committed to by the filter `data`,
+/// built for the block with hash `block_hash`.
+pub fn filter_matches(data: &[u8], block_hash: BlockHash, targets: &[ScriptBuf]) -> bool {
+    if targets.is_empty() {
+        return false;
+    }
+    let (k0, k1) = filter_key(&block_hash);
+    let Some((n, body)) = read_varint(data) else {
+        return false;
+    };
+    if n == 0 {
+        return false;
+    }
+    let f = n * M;
+
+    let mut target_hashes: Vec<u64> = targets
+        .iter()
+        .map(|s| hash_to_range(k0, k1, f, s.as_bytes()))
+        .collect();
+    target_hashes.sort_unstable();
+
+    let filter_hashes = decode_filter(body, n);
+
+    // Both lists are sorted; a single linear merge tells us whether they intersect.
+    let mut i = 0;
+    let mut j = 0;
+    while i < target_hashes.len() && j < filter_hashes.len() {
+        match target_hashes[i].cmp(&filter_hashes[j]) {
+            std::cmp::Ordering::Equal => return true,
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+        }
+    }
+    false
+}
+
+/// Derives the SipHash-2-4 key from the first 16 bytes of a block hash
+fn filter_key(block_hash: &BlockHash) -> (u64, u64) {
+    let bytes = block_hash.as_byte_array();
+    let k0 = u64::from_le_bytes(bytes[0..8].try_into().expect("8 bytes"));
+    let k1 = u64::from_le_bytes(bytes[8..16].try_into().expect("8 bytes"));
+    (k0, k1)
+}
+
+fn hash_to_range(k0: u64, k1: u64, f: u64, data: &[u8]) -> u64 {
+    let hash = siphash24(k0, k1, data);
+    ((u128::from(hash) * u128::from(f)) >> 64) as u64
+}
+
+fn encode_filter(elements: &[&[u8]], k0: u64, k1: u64) -> Vec<u8> {
+    let n = elements.len() as u64;
+    let f = n * M;
+
+    let mut hashes: Vec<u64> = elements
+        .iter()
+        .map(|e| hash_to_range(k0, k1, f, e))
+        .collect();
+    hashes.sort_unstable();
+
+    let mut out = Vec::new();
+    write_varint(&mut out, n);
+
+    let mut writer = BitWriter::new();
+    let mut prev = 0u64;
+    for h in hashes {
+        let delta = h - prev;
+        prev = h;
+        golomb_encode(&mut writer, delta);
+    }
+    out.extend(writer.finish());
+    out
+}
+
+fn decode_filter(body: &[u8], n: u64) -> Vec<u64> {
+    let mut reader = BitReader::new(body);
+    let mut values = Vec::with_capacity(n as usize);
+    let mut acc = 0u64;
+    for _ in 0..n {
+        let delta = golomb_decode(&mut reader);
+        acc += delta;
+        values.push(acc);
+    }
+    values
+}
+
+/// Writes `value` with Golomb-Rice coding: `value >> P` unary bits followed by the
+/// low `P` bits of `value`, per BIP158.
+fn golomb_encode(writer: &mut BitWriter, value: u64) {
+    let quotient = value >> P;
+    for _ in 0..quotient {
+        writer.write_bit(true);
+    }
+    writer.write_bit(false);
+    for i in (0..P).rev() {
+        writer.write_bit((value >> i) & 1 == 1);
+    }
+}
+
+fn golomb_decode(reader: &mut BitReader) -> u64 {
+    let mut quotient = 0u64;
+    while reader.read_bit() {
+        quotient += 1;
+    }
+    let mut remainder = 0u64;
+    for _ in 0..P {
+        remainder = (remainder << 1) | u64::from(reader.read_bit());
+    }
+    (quotient << P) | remainder
+}
+
+/// Writes a Bitcoin "CompactSize" varint
+fn write_varint(out: &mut Vec<u8>, n: u64) {
+    if n < 0xfd {
+        out.push(n as u8);
+    } else if n <= 0xffff {
+        out.push(0xfd);
+        out.extend_from_slice(&(n as u16).to_le_bytes());
+    } else if n <= 0xffff_ffff {
+        out.push(0xfe);
+        out.extend_from_slice(&(n as u32).to_le_bytes());
+    } else {
+        out.push(0xff);
+        out.extend_from_slice(&n.to_le_bytes());
+    }
+}
+
+/// Reads a Bitcoin "CompactSize" varint, returning the value and remaining bytes
+fn read_varint(data: &[u8]) -> Option<(u64, &[u8])> {
+    let (&tag, rest) = data.split_first()?;
+    match tag {
+        0xfd => {
+            let (bytes, rest) = rest.split_at_checked(2)?;
+            Some((u16::from_le_bytes(bytes.try_into().ok()?) as u64, rest))
+        }
+        0xfe => {
+            let (bytes, rest) = rest.split_at_checked(4)?;
+            Some((u32::from_le_bytes(bytes.try_into().ok()?) as u64, rest))
+        }
+        0xff => {
+            let (bytes, rest) = rest.split_at_checked(8)?;
+            Some((u64::from_le_bytes(bytes.try_into().ok()?), rest))
+        }
+        n => Some((n as u64, rest)),
+    }
+}
+
+/// Minimal MSB-first bit writer
+struct BitWriter {
+    bytes: Vec<u8>,
+    cur: u8,
+    nbits: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            cur: 0,
+            nbits: 0,
+        }
+    }
+
+    fn write_bit(&mut self, bit: bool) {
+        self.cur = (self.cur << 1) | u8::from(bit);
+        self.nbits += 1;
+        if self.nbits == 8 {
+            self.bytes.push(self.cur);
+            self.cur = 0;
+            self.nbits = 0;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.nbits > 0 {
+            self.cur <<= 8 - self.nbits;
+            self.bytes.push(self.cur);
+        }
+        self.bytes
+    }
+}
+
+/// Minimal MSB-first bit reader
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    byte_pos: usize,
+    bit_pos: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            byte_pos: 0,
+            bit_pos: 0,
+        }
+    }
+
+    fn read_bit(&mut self) -> bool {
+        let byte = self.bytes.get(self.byte_pos).copied().unwrap_or(0);
+        let bit = (byte >> (7 - self.bit_pos)) & 1 == 1;
+        self.bit_pos += 1;
+        if self.bit_pos == 8 {
+            self.bit_pos = 0;
+            self.byte_pos += 1;
+        }
+        bit
+    }
+}
+
+/// SipHash-2-4 (2 compression rounds, 4 finalization rounds) over `data` keyed by `(k0, k1)`
+fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v0 = 0x736f6d6570736575u64 ^ k0;
+    let mut v1 = 0x646f72616e646f6du64 ^ k1;
+    let mut v2 = 0x6c7967656e657261u64 ^ k0;
+    let mut v3 = 0x7465646279746573u64 ^ k1;
+
+    let len = data.len();
+    let end = len - (len % 8);
+    let mut i = 0;
+    while i < end {
+        let block = u64::from_le_bytes(data[i..i + 8].try_into().expect("8 bytes"));
+        v3 ^= block;
+        sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+        sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+        v0 ^= block;
+        i += 8;
+    }
+
+    let mut tail = [0u8; 8];
+    tail[..len - end].copy_from_slice(&data[end..]);
+    let last = u64::from_le_bytes(tail) | ((len as u64) << 56);
+    v3 ^= last;
+    sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+    sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+    v0 ^= last;
+
+    v2 ^= 0xff;
+    sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+    sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+    sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+    sipround(&mut v0, &mut v1, &mut v2, &mut v3);
+
+    v0 ^ v1 ^ v2 ^ v3
+}
+
+fn sipround(v0: &mut u64, v1: &mut u64, v2: &mut u64, v3: &mut u64) {
+    *v0 = v0.wrapping_add(*v1);
+    *v1 = v1.rotate_left(13);
+    *v1 ^= *v0;
+    *v0 = v0.rotate_left(32);
+
+    *v2 = v2.wrapping_add(*v3);
+    *v3 = v3.rotate_left(16);
+    *v3 ^= *v2;
+
+    *v0 = v0.wrapping_add(*v3);
+    *v3 = v3.rotate_left(21);
+    *v3 ^= *v0;
+
+    *v2 = v2.wrapping_add(*v1);
+    *v1 = v1.rotate_left(17);
+    *v1 ^= *v2;
+    *v2 = v2.rotate_left(32);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn varint_roundtrip() {
+        for n in [0u64, 1, 252, 253, 65_535, 65_536, u32::MAX as u64, u32::MAX as u64 + 1] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, n);
+            let (decoded, rest) = read_varint(&buf).unwrap();
+            assert_eq!(decoded, n);
+            assert!(rest.is_empty());
+        }
+    }
+
+    #[test]
+    fn golomb_roundtrip() {
+        let values = [0u64, 1, 42, 1 << 19, (1 << 19) + 7, 10_000_000];
+        let mut writer = BitWriter::new();
+        for v in values {
+            golomb_encode(&mut writer, v);
+        }
+        let bytes = writer.finish();
+        let mut reader = BitReader::new(&bytes);
+        for v in values {
+            assert_eq!(golomb_decode(&mut reader), v);
+        }
+    }
+
+    #[test]
+    fn filter_matches_own_output_scripts() {
+        let data = hex!("00002020441f39efccdb87b456dbc0a46cb7b75a7fde865ad0d60d115a00000000000000ce88773ce45bd5cd3f5f2bcfce119ff6a99b0f772817c94df41a035d68b5f85162d422658cec001a0279ab7e03010000000001010000000000000000000000000000000000000000000000000000000000000000ffffffff1a03a49f26012013090909200909200904da240016bd1103000000ffffffff02d0f8120000000000160014820d4a343a44e915c36494995c2899abe37418930000000000000000266a24aa21a9edbdc662966a0f845f7c8ca21f10dbfdad3546e6ed945ba690b217dd04695c42dd012000000000000000000000000000000000000000000000000000000000000000000000000002000000016f1b6f9615c959f01db94242cdd6be6df30c16f10577fc994ddb738a56bdbf55010000006a473044022029f7f30e6b4fd1b99daf77070ff01887f2c233828b2989494252dacc3bb465fd0220419986c34074556188ac33e6607f1e9bbe1c16459413832e4f7d30e9069df59f012102c5faee837f09ec075734fa77b8b895397ff9c93b2987db845e2f5e47000a5226fdffffff028aca1100000000001976a914bf3394503fc358700f2dd3388296c6ff5ab245a488ac6d695c94010000001976a91413cd3dcae193017f36a33152c4b01c2390e74fed88aca29f260002000000000101deb21f0a88775bad7a984650ab36f07dc25492e5241020b381793b5b1470cca10000000000fdffffff023ee2010000000000160014633bf3c375206e357ff31754be6c4a858733571fe803000000000000160014b1cbd2ca1b6eb558dc9210e9bd13600413ab3f2802473044022025e2158ec5dc5cdf5dcf766a583ac9784ce4c37de3b59f9d61933498c8df38190220350d93d1cd07af15e17dcd90cc1cee61948bb3907dbd15b9f75dfd4ea4b4825d012102e1798b4c71209f4ab7f63b348335459313a2b23127e26cf3e8b62082f1897086a29f2600");
+        let block: Block = bitcoin::consensus::encode::deserialize(&data).unwrap();
+
+        let filter = build_filter(&block);
+        let target = block.txdata[1].output[0].script_pubkey.clone();
+        assert!(filter_matches(&filter, block.block_hash(), &[target]));
+
+        let miss = ScriptBuf::from_hex("76a914000000000000000000000000000000000000000088ac").unwrap();
+        assert!(!filter_matches(&filter, block.block_hash(), &[miss]));
+    }
+}
@@ -1,6 +1,3 @@
-use std::thread;
-use std::time::Duration;
-
 use bitcoin::Amount;
 use bitcoin::Txid;
 
@@ -19,9 +16,16 @@ pub mod audittx;
 pub mod blockmk;
 pub mod cluster;
 pub mod error;
+pub mod filter;
+pub mod report;
+pub mod script;
 pub mod sigops;
 pub mod taproot;
+pub mod template;
+#[cfg(feature = "bitcoinconsensus")]
+pub mod txverify;
 pub mod util;
+pub mod watch;
 
 mod macros {
     #[macro_export]
@@ -63,9 +67,9 @@ pub trait Cluster {
     fn set_links(&mut self, uid: usize);
 }
 
-/// Computes the number of dust-producing transactions in the given block. Note that
-/// the function defines dust as 2x the normal [`DUST_LIMIT`].
-pub fn check_dust_pruned(block: &bitcoin::Block) -> Option<(usize, usize)> {
+/// Computes a [`report::DustReport`] for the given block. Note that the function defines
+/// dust as 2x the normal [`DUST_LIMIT`]. Returns `None` if the block produced no dust.
+pub fn check_dust_pruned_report(block: &bitcoin::Block) -> Option<report::DustReport> {
     let mut dust_outputs = 0usize;
     let mut dust_tx_count = 0usize;
     let txs = &block.txdata;
@@ -86,13 +90,98 @@ pub fn check_dust_pruned(block: &bitcoin::Block) -> Option<(usize, usize)> {
         }
     }
     if dust_tx_count > 0 {
-        return Some((dust_outputs, dust_tx_count));
+        return Some(report::DustReport {
+            dust_txo_count: dust_outputs,
+            dust_tx_count,
+            dust_ratio: None,
+        });
     }
     None
 }
 
-/// Computes the number of dust-producing transactions in the given block along with
-/// the fraction of total block weight attributable to dust.
+/// Computes the number of dust-producing transactions in the given block. Note that
+/// the function defines dust as 2x the normal [`DUST_LIMIT`].
+///
+/// Thin tuple shim over [`check_dust_pruned_report`]; prefer that for new code.
+pub fn check_dust_pruned(block: &bitcoin::Block) -> Option<(usize, usize)> {
+    check_dust_pruned_report(block).map(|r| (r.dust_txo_count, r.dust_tx_count))
+}
+
+/// Number of spending tx to resolve per round-trip in [`resolve_prevouts`]
+const PREVOUT_BATCH_SIZE: usize = 20;
+
+/// Resolves every prevout spent by `block`'s non-coinbase inputs into a single
+/// `OutPoint -> TxOut` map. `getrawtransaction` doesn't populate per-input prevout data
+/// on the spending tx itself, so for each distinct prevout txid we instead fetch *that*
+/// tx and read the output it's spending directly off its `vout` list, via real JSON-RPC
+/// batch requests of up to [`PREVOUT_BATCH_SIZE`] tx each (one HTTP round-trip per round,
+/// not one per tx). The resulting map is reusable by any caller that needs this block's
+/// prevouts, e.g. [`crate::txverify::verify_block_scripts`].
+pub fn resolve_prevouts(
+    block: &bitcoin::Block,
+    core: &Client,
+) -> Result<HashMap<bitcoin::OutPoint, bitcoin::TxOut>> {
+    let outpoints: Vec<bitcoin::OutPoint> = block
+        .txdata
+        .iter()
+        .filter(|tx| !tx.is_coinbase())
+        .flat_map(|tx| tx.input.iter().map(|input| input.previous_output))
+        .collect();
+
+    let prevout_txids: Vec<Txid> = {
+        let mut seen = HashSet::new();
+        outpoints
+            .iter()
+            .map(|outpoint| outpoint.txid)
+            .filter(|txid| seen.insert(*txid))
+            .collect()
+    };
+
+    let mut prevout_txs: HashMap<Txid, bitcoincore_rpc_json::GetRawTransactionResult> =
+        HashMap::new();
+
+    let rpc = core.get_jsonrpc_client();
+    for round in prevout_txids.chunks(PREVOUT_BATCH_SIZE) {
+        let params: Vec<_> = round
+            .iter()
+            .map(|txid| bitcoincore_rpc::jsonrpc::arg(serde_json::json!([txid.to_string(), true])))
+            .collect();
+        let requests: Vec<_> = params
+            .iter()
+            .map(|p| rpc.build_request("getrawtransaction", Some(p)))
+            .collect();
+        let responses = rpc.send_batch(&requests).map_err(bitcoincore_rpc::Error::from)?;
+
+        for (txid, response) in round.iter().zip(responses) {
+            let response = response.expect("bitcoind returns one response per batched request");
+            let tx_info: bitcoincore_rpc_json::GetRawTransactionResult =
+                response.result().map_err(bitcoincore_rpc::Error::from)?;
+            prevout_txs.insert(*txid, tx_info);
+        }
+    }
+
+    let mut prevouts = HashMap::new();
+    for outpoint in outpoints {
+        let Some(tx_info) = prevout_txs.get(&outpoint.txid) else {
+            continue;
+        };
+        let Some(vout) = tx_info.vout.get(outpoint.vout as usize) else {
+            continue;
+        };
+        prevouts.insert(
+            outpoint,
+            bitcoin::TxOut {
+                value: vout.value,
+                script_pubkey: bitcoin::ScriptBuf::from_bytes(vout.script_pub_key.hex.clone()),
+            },
+        );
+    }
+
+    Ok(prevouts)
+}
+
+/// Computes a [`report::DustReport`] for the given block, including the fraction of
+/// total block weight attributable to dust.
 ///
 /// ## Errors
 /// if `get_raw_transaction_info_verbose` returns an error
@@ -102,19 +191,14 @@ pub fn check_dust_pruned(block: &bitcoin::Block) -> Option<(usize, usize)> {
 /// the result catches tx at or near the threshold. Further, if the value of dust-producing
 /// outputs for a tx is at least 50% of the total tx value (less fees), then the total tx weight
 /// is counted toward the returned dust ratio.
-pub fn check_dust_full(block: &bitcoin::Block, core: &Client) -> Result<(usize, usize, f64)> {
-    /* return a tuple
-    (
-        dust_txo_count,
-        tx_count_producing_dust,
-        block_dust_ratio,
-    )
-    */
+pub fn check_dust_full_report(block: &bitcoin::Block, core: &Client) -> Result<report::DustReport> {
     let block_wu = block.weight().to_wu();
     let mut dust_wu = 0u64;
     let mut dust_outputs = 0usize;
     let mut dust_tx_count = 0usize;
 
+    let prevouts = resolve_prevouts(block, core)?;
+
     for tx in &block.txdata {
         if tx.is_coinbase() {
             continue;
@@ -124,9 +208,8 @@ pub fn check_dust_full(block: &bitcoin::Block, core: &Client) -> Result<(usize,
 
         // Get tx value from prevouts
         let mut tx_value = 0u64;
-        let tx_info = core.get_raw_transaction_info_verbose(&tx.txid(), None)?;
-        for input in &tx_info.vin {
-            let prevout = input.prevout.as_ref().expect("input has prevout");
+        for input in &tx.input {
+            let prevout = prevouts.get(&input.previous_output).expect("input has prevout");
             tx_value += prevout.value.to_sat();
         }
 
@@ -153,14 +236,28 @@ pub fn check_dust_full(block: &bitcoin::Block, core: &Client) -> Result<(usize,
             // add this tx weight
             dust_wu += tx_wu;
         }
-
-        // to avoid overwhelming bitcoind's rpc interface, wait briefly between iterations
-        thread::sleep(Duration::from_millis(100));
     }
 
     let dust_ratio = (dust_wu as f64 / block_wu as f64).trunc_three();
 
-    Ok((dust_outputs, dust_tx_count, dust_ratio))
+    Ok(report::DustReport {
+        dust_txo_count: dust_outputs,
+        dust_tx_count,
+        dust_ratio: Some(dust_ratio),
+    })
+}
+
+/// Computes the number of dust-producing transactions in the given block along with
+/// the fraction of total block weight attributable to dust.
+///
+/// Thin tuple shim over [`check_dust_full_report`]; prefer that for new code.
+pub fn check_dust_full(block: &bitcoin::Block, core: &Client) -> Result<(usize, usize, f64)> {
+    let report = check_dust_full_report(block, core)?;
+    Ok((
+        report.dust_txo_count,
+        report.dust_tx_count,
+        report.dust_ratio.expect("check_dust_full_report always sets dust_ratio"),
+    ))
 }
 
 /// Scores a newly connected block on its similarity to a given set of txids
@@ -195,6 +292,33 @@ pub fn block_audit(block: &bitcoin::Block, projected: &[Txid]) -> f64 {
     ((num_actual - num_unseen) / num_actual).trunc_three() * 100.0
 }
 
+/// Scores a newly connected block on its similarity to `projected`, like [`block_audit`],
+/// and packages the result as a [`report::BlockAuditReport`] that also records `height`
+/// and the block's hash, so a long-running auditor can accumulate a time series of
+/// per-block scores.
+pub fn block_audit_report(
+    height: u64,
+    block: &bitcoin::Block,
+    projected: &[Txid],
+) -> report::BlockAuditReport {
+    let actual_count = block.txdata.iter().filter(|tx| !tx.is_coinbase()).count();
+    let unseen_count = block
+        .txdata
+        .iter()
+        .filter(|tx| !tx.is_coinbase() && !projected.contains(&tx.txid()))
+        .count();
+    let score = block_audit(block, projected);
+
+    report::BlockAuditReport {
+        height,
+        block_hash: block.block_hash(),
+        projected_count: projected.len(),
+        actual_count,
+        unseen_count,
+        score,
+    }
+}
+
 /// Returns block subsidy from the given `height`
 pub fn subsidy(height: u32) -> Amount {
     // see bitcoin/src/validation.cpp#GetBlockSubsidy
@@ -211,6 +335,7 @@ pub struct TestMempoolEntry {
     pub uid: usize,
     pub fee: u64,
     pub weight: u64,
+    pub sigops: u32,
     pub parents: HashSet<usize>,
 }
 
@@ -227,6 +352,7 @@ impl Audit for Vec<TestMempoolEntry> {
                     order: u32::try_from(uid).unwrap(),
                     weight: entry.weight,
                     fee: entry.fee,
+                    sigops: entry.sigops,
                     parents: entry.parents,
                     ..Default::default()
                 };
@@ -341,15 +467,27 @@ mod test {
             )
             .unwrap(),
         }];
-        let _tx = Transaction {
+        let tx = Transaction {
             version: transaction::Version::ONE,
             lock_time: locktime,
             input: vin,
             output: vout,
         };
 
-        //TODO
-        //assert!(tx.is_consensus_valid());
+        // The spent output is P2SH-wrapped P2WPKH; scriptSig carries the redeem script
+        // directly, so the prevout scriptPubKey is recoverable as hash160(redeem_script).
+        #[cfg(feature = "bitcoinconsensus")]
+        {
+            let redeem_script =
+                ScriptBuf::from_hex("0014e83d1d02a3844c34995ec3fc1ef0b49bf02936f5").unwrap();
+            let prevout = TxOut {
+                value: Amount::from_sat(13_220_900),
+                script_pubkey: ScriptBuf::new_p2sh(&redeem_script.script_hash()),
+            };
+            assert!(crate::txverify::verify_tx(&tx, &[prevout], crate::txverify::STANDARD_FLAGS)
+                .iter()
+                .all(|(_, res)| res.is_ok()));
+        }
     }
 
     #[test]
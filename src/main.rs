@@ -1,6 +1,7 @@
 //#![allow(unused)]
 #![warn(clippy::all)]
 use crate::cli::{Args, Cmd};
+use bitcoin::Network;
 use bitcoincore_rpc::{Auth, Client};
 use clap::Parser;
 
@@ -11,28 +12,37 @@ fn main() -> anyhow::Result<()> {
     pretty_env_logger::init_timed();
     let args = Args::parse();
 
-    // By default core is running on local host.
-    // TODO: consider allowing set server url
-    let mut url = String::from("http://127.0.0.1:");
-
-    // Set the default port for network
     // default signet
     let net = args.network.unwrap_or_default();
-    let port = match net.as_str() {
-        "bitcoin" => "8332",
-        "testnet" => "18332",
-        "regtest" => "18443",
-        _ => "38332", // signet
+    let network = match net.as_str() {
+        "bitcoin" => Network::Bitcoin,
+        "testnet" => Network::Testnet,
+        "regtest" => Network::Regtest,
+        _ => Network::Signet,
     };
-    url.push_str(port);
 
-    let cookie = args.rpc_cookie.unwrap_or_default();
-    let auth = Auth::CookieFile(cookie.into());
+    // By default core is running on local host, on the network's default port.
+    // `--rpc-url` overrides this entirely.
+    let url = args.rpc_url.unwrap_or_else(|| {
+        let port = match net.as_str() {
+            "bitcoin" => "8332",
+            "testnet" => "18332",
+            "regtest" => "18443",
+            _ => "38332", // signet
+        };
+        format!("http://127.0.0.1:{port}")
+    });
+
+    // Prefer explicit user/pass auth when both are given, falling back to the cookie file
+    let auth = match (args.rpc_user, args.rpc_pass) {
+        (Some(user), Some(pass)) => Auth::UserPass(user, pass),
+        _ => Auth::CookieFile(args.rpc_cookie.unwrap_or_default().into()),
+    };
     let core = Client::new(&url, auth)?;
 
     match args.cmd {
         Cmd::Hash => cmd::hash(&core)?,
-        Cmd::Script { hex } => cmd::parse_script(&hex)?,
+        Cmd::Script { hex } => cmd::parse_script(&hex, network)?,
         Cmd::Fee(cmd) => cmd::fee::execute(&core, cmd)?,
         Cmd::Audit(cmd) => cmd::audit::execute(&core, cmd)?,
         Cmd::Tr(cmd) => cmd::tr::execute(&core, cmd)?,
@@ -0,0 +1,61 @@
+use super::*;
+use serde::Serialize;
+use std::io::Write;
+
+/// A dust-accounting snapshot for a single block. See [`crate::check_dust_pruned`] and
+/// [`crate::check_dust_full`].
+#[derive(Debug, Clone, Serialize)]
+pub struct DustReport {
+    pub dust_txo_count: usize,
+    pub dust_tx_count: usize,
+    /// Fraction of block weight attributable to dust. `None` in pruned mode, where
+    /// prevout values aren't available to compute it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dust_ratio: Option<f64>,
+}
+
+/// A [`crate::block_audit`] snapshot for a single connected block
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockAuditReport {
+    pub height: u64,
+    pub block_hash: bitcoin::BlockHash,
+    pub projected_count: usize,
+    pub actual_count: usize,
+    pub unseen_count: usize,
+    pub score: f64,
+}
+
+/// Output format for [`export`]
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    /// Newline-delimited JSON, one object per line
+    Ndjson,
+    /// Comma-separated values, with a header row
+    Csv,
+}
+
+/// Writes a series of reports to `writer` in the given `format`, so a long-running
+/// auditor can accumulate a machine-readable time series of per-block statistics.
+pub fn export<T, W>(reports: &[T], format: ExportFormat, writer: W) -> Result<()>
+where
+    T: Serialize,
+    W: Write,
+{
+    match format {
+        ExportFormat::Ndjson => {
+            let mut writer = writer;
+            for report in reports {
+                serde_json::to_writer(&mut writer, report)?;
+                writer.write_all(b"\n")?;
+            }
+        },
+        ExportFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(writer);
+            for report in reports {
+                wtr.serialize(report)?;
+            }
+            wtr.flush()?;
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use super::*;
+use crate::util::pushnum_value;
+use bitcoin::opcodes::all::OP_CHECKMULTISIG;
+use bitcoin::script::Instruction;
+use bitcoin::{Address, Network, Script};
+
+/// The standard output type of a scriptPubKey
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptType {
+    PubKey,
+    PubKeyHash,
+    ScriptHash,
+    WitnessV0KeyHash,
+    WitnessV0ScriptHash,
+    Taproot,
+    /// Bare `m`-of-`n` multisig
+    Multisig { m: u8, n: u8 },
+    /// `OP_RETURN` payload
+    NullData,
+    NonStandard,
+}
+
+/// A scriptPubKey along with its classified [`ScriptType`] and, where one can be
+/// derived, the corresponding [`Address`]
+#[derive(Debug, Clone)]
+pub struct ClassifiedScript {
+    pub script_type: ScriptType,
+    pub address: Option<Address>,
+}
+
+/// Classifies `script` as a standard output type and derives its [`Address`] on
+/// `network`, if one exists for that type.
+pub fn classify(script: &Script, network: Network) -> ClassifiedScript {
+    let script_type = script_type(script);
+    let address = Address::from_script(script, network).ok();
+    ClassifiedScript {
+        script_type,
+        address,
+    }
+}
+
+fn script_type(script: &Script) -> ScriptType {
+    if script.is_p2pk() {
+        ScriptType::PubKey
+    } else if script.is_p2pkh() {
+        ScriptType::PubKeyHash
+    } else if script.is_p2sh() {
+        ScriptType::ScriptHash
+    } else if script.is_p2wpkh() {
+        ScriptType::WitnessV0KeyHash
+    } else if script.is_p2wsh() {
+        ScriptType::WitnessV0ScriptHash
+    } else if script.is_p2tr() {
+        ScriptType::Taproot
+    } else if script.is_op_return() {
+        ScriptType::NullData
+    } else if let Some((m, n)) = bare_multisig(script) {
+        ScriptType::Multisig { m, n }
+    } else {
+        ScriptType::NonStandard
+    }
+}
+
+/// Returns `(m, n)` if `script` is a bare `m`-of-`n` multisig output
+/// (`OP_PUSHNUM_m <pubkey>{n} OP_PUSHNUM_n OP_CHECKMULTISIG`)
+fn bare_multisig(script: &Script) -> Option<(u8, u8)> {
+    let instructions: Vec<Instruction> = script.instructions().filter_map(Result::ok).collect();
+    let (last, rest) = instructions.split_last()?;
+    if !matches!(last, Instruction::Op(op) if *op == OP_CHECKMULTISIG) {
+        return None;
+    }
+
+    let (n_instr, pubkeys) = rest.split_last()?;
+    let Instruction::Op(n_op) = n_instr else {
+        return None;
+    };
+    let n = pushnum_value(*n_op)?;
+
+    let (m_instr, pubkeys) = pubkeys.split_first()?;
+    let Instruction::Op(m_op) = m_instr else {
+        return None;
+    };
+    let m = pushnum_value(*m_op)?;
+
+    if m > n || pubkeys.len() as u32 != n {
+        return None;
+    }
+    if !pubkeys
+        .iter()
+        .all(|instr| matches!(instr, Instruction::PushBytes(_)))
+    {
+        return None;
+    }
+
+    Some((m as u8, n as u8))
+}
+
+/// Whether `script` consists only of data pushes, a prerequisite for standardness
+/// when used as a scriptSig.
+pub fn is_push_only(script: &Script) -> bool {
+    script.is_push_only()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn classify_p2wpkh() {
+        let script =
+            bitcoin::ScriptBuf::from_hex("0014170ef448a233262c316d983f3f76ff9941df5e17").unwrap();
+        let res = classify(&script, Network::Bitcoin);
+        assert_eq!(res.script_type, ScriptType::WitnessV0KeyHash);
+        assert!(res.address.is_some());
+    }
+
+    #[test]
+    fn classify_op_return() {
+        let script = bitcoin::ScriptBuf::from_hex(
+            "6a24aa21a9edbdc662966a0f845f7c8ca21f10dbfdad3546e6ed945ba690b217dd04695c42dd",
+        )
+        .unwrap();
+        let res = classify(&script, Network::Bitcoin);
+        assert_eq!(res.script_type, ScriptType::NullData);
+        assert!(res.address.is_none());
+    }
+
+    #[test]
+    fn classify_bare_2_of_3_multisig() {
+        let script = bitcoin::ScriptBuf::from_hex("5221020c1929d70ed907e2a8d20fb4cd356a325367a4f667b2a6b441632773c5cb42e6210349a4cb2b92fa9bb579ee73b5d0cedc6e796d60584a173813960b43d4868976012103f01a75f7d5c2e03226bfec90291cd78643d60adfee8b03e81642b804b2b814d453ae").unwrap();
+        let res = classify(&script, Network::Bitcoin);
+        assert_eq!(res.script_type, ScriptType::Multisig { m: 2, n: 3 });
+    }
+
+    #[test]
+    fn classify_nonstandard() {
+        let script = bitcoin::ScriptBuf::from_hex("6e").unwrap(); // lone OP_2DUP
+        let res = classify(&script, Network::Bitcoin);
+        assert_eq!(res.script_type, ScriptType::NonStandard);
+    }
+}
@@ -1,16 +1,18 @@
 use super::*;
-use crate::hex;
+use crate::util::pushnum_value;
+use bitcoin::opcodes::all::{
+    OP_CHECKMULTISIG, OP_CHECKMULTISIGVERIFY, OP_CHECKSIG, OP_CHECKSIGVERIFY,
+};
+use bitcoin::opcodes::Opcode;
+use bitcoin::script::Instruction;
+use bitcoin::Script;
 use bitcoin::ScriptBuf;
 use bitcoincore_rpc_json::{GetRawTransactionResult, ScriptPubkeyType};
-use lazy_static::lazy_static;
-use regex_lite::Regex;
 
 /// Segwit scale factor when comparing tx size
 const SEGWIT_SCALAR: u32 = 4;
-
-lazy_static! {
-    static ref RE: Regex = Regex::new(r".*OP_(PUSHNUM_)?(\d{1,2}) OP_CHECKMULTISIG.*").unwrap();
-}
+/// Sigops attributed to a multisig op whose key count can't be determined
+const MAX_PUBKEYS_PER_MULTISIG: u32 = 20;
 
 /// Counts signature operations for a `tx`
 pub fn get_sigops_count(tx: &GetRawTransactionResult) -> u32 {
@@ -18,8 +20,10 @@ pub fn get_sigops_count(tx: &GetRawTransactionResult) -> u32 {
 
     for input in &tx.vin {
         let scriptsig = input.script_sig.as_ref().expect("has scriptsig");
-        // get script sig raw sigops
-        sigops += script_sigops_count_raw(&scriptsig.asm);
+        let scriptsig_buf = ScriptBuf::from_bytes(scriptsig.hex.clone());
+
+        // scriptSig is counted inaccurately, matching Core's top-level script counting
+        sigops += count_script_sigops(&scriptsig_buf, /* accurate: */ false);
 
         // get prevout spk type
         let prevout = input.prevout.as_ref().expect("has prevout");
@@ -32,26 +36,25 @@ pub fn get_sigops_count(tx: &GetRawTransactionResult) -> u32 {
                         1
                     } else if data[1] == 0x00 && data[2] == 0x20 {
                         // sh-wsh
-                        let script =
-                            script_try_from_witness(&input.txinwitness).unwrap_or_default();
-                        script_sigops_count(&script.to_asm_string())
+                        let script = script_try_from_witness(&input.txinwitness).unwrap_or_default();
+                        count_script_sigops(&script, true)
                     } else {
                         // legacy p2sh
-                        let redeem_script = parse_p2sh_redeem_script(&scriptsig.asm);
-                        script_sigops_count(&redeem_script.to_asm_string()) * SEGWIT_SCALAR
+                        let redeem_script = parse_p2sh_redeem_script(&scriptsig_buf);
+                        count_script_sigops(&redeem_script, true) * SEGWIT_SCALAR
                     }
                 }
                 ScriptPubkeyType::Witness_v0_KeyHash => 1,
                 ScriptPubkeyType::Witness_v0_ScriptHash | ScriptPubkeyType::Witness_Unknown => {
                     let script = script_try_from_witness(&input.txinwitness).unwrap_or_default();
-                    script_sigops_count(&script.to_asm_string())
+                    count_script_sigops(&script, true)
                 }
                 ScriptPubkeyType::Pubkey | ScriptPubkeyType::PubkeyHash => SEGWIT_SCALAR,
                 ScriptPubkeyType::MultiSig | ScriptPubkeyType::Nonstandard => {
-                    let prevout_spk = &prevout.script_pub_key;
-                    script_sigops_count_raw(&prevout_spk.asm)
+                    let prevout_spk = ScriptBuf::from_bytes(prevout.script_pub_key.hex.clone());
+                    count_script_sigops(&prevout_spk, false)
                 }
-                // ScriptPubkeyType::Witness_v1_Taproot
+                // ScriptPubkeyType::Witness_v1_Taproot contributes 0, per BIP341
                 // ScriptPubkeyType::NullData
                 _ => 0,
             };
@@ -61,41 +64,36 @@ pub fn get_sigops_count(tx: &GetRawTransactionResult) -> u32 {
     sigops
 }
 
-/// Finds sigops cost from Script (as asm string slice) in p2sh redeem script or witness field
-fn script_sigops_count(script: &str) -> u32 {
-    let mut sigops = 0_u32;
-
-    // count OP_CHECKMULTISIG
-    let matches: Vec<&str> = script.matches("OP_CHECKMULTISIG").collect();
-    for _ in 0..matches.len() {
-        if let Some(cap) = RE.captures(script) {
-            // redeem script or witness
-            // +N in OP_N where N is total number of keys in multisig
-            let n: u32 = cap[2].to_owned().parse().expect("parse int");
-            sigops += if n <= 16 { n } else { 20 };
-        } else {
-            // number of pubkeys missing ?
-            sigops += 20;
-        }
-    }
-
-    sigops
-}
-
-/// Finds sigops cost from Script (as asm string slice) in raw `ScriptSig` and `ScriptPubkey`
-fn script_sigops_count_raw(script: &str) -> u32 {
+/// Walks `script`'s instructions, counting `OP_CHECKSIG[VERIFY]` as 1 sigop each and
+/// `OP_CHECKMULTISIG[VERIFY]` as [`MAX_PUBKEYS_PER_MULTISIG`], unless `accurate` is `true`
+/// and the multisig op is immediately preceded by `OP_PUSHNUM_N` (1 <= N <= 16), in which
+/// case it counts as `N`.
+fn count_script_sigops(script: &Script, accurate: bool) -> u32 {
     let mut sigops = 0u32;
+    let mut prev_op: Option<Opcode> = None;
+
+    for instruction in script.instructions() {
+        let Ok(instruction) = instruction else {
+            break;
+        };
+
+        match instruction {
+            Instruction::Op(op) if op == OP_CHECKSIG || op == OP_CHECKSIGVERIFY => {
+                sigops += 1;
+            }
+            Instruction::Op(op) if op == OP_CHECKMULTISIG || op == OP_CHECKMULTISIGVERIFY => {
+                sigops += match prev_op.filter(|_| accurate).and_then(pushnum_value) {
+                    Some(n) => n,
+                    None => MAX_PUBKEYS_PER_MULTISIG,
+                };
+            }
+            _ => {}
+        }
 
-    // bare multisig
-    let matches: Vec<&str> = script.matches("OP_CHECKMULTISIG").collect();
-    for _ in 0..matches.len() {
-        sigops += 20 * SEGWIT_SCALAR;
-    }
-
-    // count OP_CHECKSIG[VERIFY]
-    let matches: Vec<&str> = script.matches("CHECKSIG").collect();
-    for _ in 0..matches.len() {
-        sigops += SEGWIT_SCALAR;
+        prev_op = match instruction {
+            Instruction::Op(op) => Some(op),
+            Instruction::PushBytes(_) => None,
+        };
     }
 
     sigops
@@ -113,47 +111,26 @@ fn script_try_from_witness(txin_witness: &Option<Vec<Vec<u8>>>) -> Option<Script
     None
 }
 
-/// Returns the redeem script from the given script (as asm string) slice
-fn parse_p2sh_redeem_script(script: &str) -> ScriptBuf {
-    // redeem script hex is last element of scriptsig
-    let redeem_script_hex = script.split(' ').last().expect("scriptsig last element");
-    let data = hex!(redeem_script_hex);
-    ScriptBuf::from_bytes(data)
-}
-
-#[allow(unused)]
-fn regex_match(input: &str) -> bool {
-    RE.is_match(input)
-}
-
-#[allow(unused)]
-fn regex_capture(input: &str) -> Option<u32> {
-    if let Some(cap) = RE.captures(input) {
-        let s: u32 = cap[2].to_owned().parse().unwrap();
-        return Some(s);
-    }
-    None
+/// Returns the redeem script from the last push of the given p2sh `scriptsig`
+fn parse_p2sh_redeem_script(scriptsig: &Script) -> ScriptBuf {
+    let last_push = scriptsig
+        .instructions()
+        .filter_map(Result::ok)
+        .filter_map(|instr| match instr {
+            Instruction::PushBytes(pb) => Some(pb.as_bytes().to_vec()),
+            Instruction::Op(_) => None,
+        })
+        .last()
+        .unwrap_or_default();
+    ScriptBuf::from_bytes(last_push)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::hex;
-    use bitcoin::Script;
-    use bitcoin::ScriptBuf;
     use bitcoin::Witness;
 
-    #[test]
-    fn test_regex() {
-        assert!(regex_match("OP_PUSHNUM_0 OP_CHECKMULTISIG"))
-    }
-
-    #[test]
-    fn test_capture() {
-        let res = regex_capture("OP_16 OP_CHECKMULTISIG");
-        assert_eq!(res, Some(16));
-    }
-
     #[test]
     fn test_witness_sigops() {
         // 2-3 multi wsh
@@ -165,42 +142,25 @@ mod test {
             ]
         );
 
-        let script = Script::from_bytes(witness.last().unwrap()).to_asm_string();
-        /*
-            OP_PUSHNUM_2
-            OP_PUSHBYTES_33 020c1929d70ed907e2a8d20fb4cd356a325367a4f667b2a6b441632773c5cb42e6
-            OP_PUSHBYTES_33 0349a4cb2b92fa9bb579ee73b5d0cedc6e796d60584a173813960b43d486897601
-            OP_PUSHBYTES_33 03f01a75f7d5c2e03226bfec90291cd78643d60adfee8b03e81642b804b2b814d4
-            OP_PUSHNUM_3
-            OP_CHECKMULTISIG
-        */
-        let res = script_sigops_count(&script);
+        let script = Script::from_bytes(witness.last().unwrap());
+        let res = count_script_sigops(script, true);
         assert_eq!(res, 3);
     }
 
+    #[test]
+    fn test_bare_multisig_inaccurate() {
+        // same script, but counted inaccurately as if it were a top-level scriptPubKey
+        let script = ScriptBuf::from_hex("5221020c1929d70ed907e2a8d20fb4cd356a325367a4f667b2a6b441632773c5cb42e6210349a4cb2b92fa9bb579ee73b5d0cedc6e796d60584a173813960b43d4868976012103f01a75f7d5c2e03226bfec90291cd78643d60adfee8b03e81642b804b2b814d453ae").unwrap();
+        let res = count_script_sigops(&script, false);
+        assert_eq!(res, MAX_PUBKEYS_PER_MULTISIG);
+    }
+
     #[test]
     fn test_parse_p2sh_redeem_script() {
         let scriptsig = ScriptBuf::from_hex("00473044022079140c84496ef0b844ac1292780cb93f88ab674dda467ec3e7abf81f1f9302ea02201ab1b057b4fd97759a82331c31e970cdb1ccb569c3520b2da97eb2d8b4e925e80147304402204f5945441105f40d04bb2590f1322f288ef65a0164154103c0ee531f5aba9d1902204ea757c8d2c935fac799012079091c704952f9d76f8cd28496aab4737ebc4cb20147304402202f21d46a43f48a49270a2f5cb95409efa1b4dd15c08af9f2f544b9172257de5c02207889db77047587dc74cc7312dd85605a405e38390313071fabea50f594adc38f014d0b01534104220936c3245597b1513a9a7fe96d96facf1a840ee21432a1b73c2cf42c1810284dd730f21ded9d818b84402863a2b5cd1afe3a3d13719d524482592fb23c88a3410472225d3abc8665cf01f703a270ee65be5421c6a495ce34830061eb0690ec27dfd1194e27b6b0b659418d9f91baec18923078aac18dc19699aae82583561fefe54104a24db5c0e8ed34da1fd3b6f9f797244981b928a8750c8f11f9252041daad7b2d95309074fed791af77dc85abdd8bb2774ed8d53379d28cd49f251b9c08cab7fc4104c64bf6e940708e7e46ccb3d65ea68c4fbfd05c1a4aedd8a1d68eefaa8233f63e24c2a03565497423b4f637f0d468d291237c481eb279260b266ec3b70e521b6854ae")
-            .unwrap()
-            .to_asm_string();
-        let redeem_script = parse_p2sh_redeem_script(&scriptsig).to_asm_string();
-        /*
-            OP_PUSHNUM_3
-            OP_PUSHBYTES_65
-            04220936c3245597b1513a9a7fe96d96facf1a840ee21432a1b73c2cf42c1810284dd730f21ded9d818b84402863a2b5cd1afe3a3d13719d524482592fb23c88a3
-            OP_PUSHBYTES_65
-            0472225d3abc8665cf01f703a270ee65be5421c6a495ce34830061eb0690ec27dfd1194e27b6b0b659418d9f91baec18923078aac18dc19699aae82583561fefe5
-            OP_PUSHBYTES_65
-            04a24db5c0e8ed34da1fd3b6f9f797244981b928a8750c8f11f9252041daad7b2d95309074fed791af77dc85abdd8bb2774ed8d53379d28cd49f251b9c08cab7fc
-            OP_PUSHBYTES_65
-            04c64bf6e940708e7e46ccb3d65ea68c4fbfd05c1a4aedd8a1d68eefaa8233f63e24c2a03565497423b4f637f0d468d291237c481eb279260b266ec3b70e521b68
-            OP_PUSHNUM_4
-            OP_CHECKMULTISIG
-        */
-
-        //dbg!(redeem_script);
-        assert!(redeem_script.contains("OP_PUSHNUM_3"));
-        assert!(redeem_script.contains("OP_PUSHNUM_4"));
-        assert!(redeem_script.contains("OP_CHECKMULTISIG"));
+            .unwrap();
+        let redeem_script = parse_p2sh_redeem_script(&scriptsig);
+
+        assert_eq!(count_script_sigops(&redeem_script, true), 4);
     }
 }
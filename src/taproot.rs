@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::str::from_utf8;
 
+use bitcoin::opcodes::all::{OP_ENDIF, OP_IF};
+use bitcoin::script::Instruction;
+use bitcoin::Block;
 use bitcoin::Script;
 use bitcoin::ScriptBuf;
 use bitcoin::Transaction;
+use bitcoin::TxIn;
 use bitcoin::TxOut;
 use lazy_static::lazy_static;
 use regex_lite::Regex;
+use serde::{Deserialize, Serialize};
 
 use crate::hex;
 
@@ -66,6 +72,145 @@ pub fn witness_elements(txin: &bitcoin::TxIn) -> impl Iterator<Item = ScriptBuf>
         .map(|bytes| Script::from_bytes(bytes).to_owned())
 }
 
+/// The ord "tag" identifying the content-type field
+const TAG_CONTENT_TYPE: u8 = 0x01;
+/// The ord "tag" identifying the content-encoding field
+const TAG_CONTENT_ENCODING: u8 = 0x09;
+/// The literal marker pushed immediately after `OP_FALSE OP_IF`
+const ORD_MARKER: &[u8] = b"ord";
+
+/// A fully decoded ordinal inscription envelope
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Inscription {
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Walks the witness script of `txin` for an ord inscription envelope
+/// (`OP_FALSE OP_IF "ord" <tag/field>* OP_0 <data>* OP_ENDIF`), reconstructing the
+/// complete content blob from every body data push between the `OP_0` separator
+/// and `OP_ENDIF`.
+pub fn parse_inscription(txin: &TxIn) -> Option<Inscription> {
+    let witness_script = txin.witness.iter().nth(1)?;
+    let script = Script::from_bytes(witness_script);
+    let instructions: Vec<Instruction> = script.instructions().filter_map(Result::ok).collect();
+
+    // Find the `OP_FALSE OP_IF` envelope start
+    let envelope_start = instructions.windows(2).position(|pair| {
+        matches!(&pair[0], Instruction::PushBytes(pb) if pb.as_bytes().is_empty())
+            && matches!(&pair[1], Instruction::Op(op) if *op == OP_IF)
+    })?;
+    let mut cursor = envelope_start + 2;
+
+    // Expect the "ord" marker
+    let Instruction::PushBytes(marker) = instructions.get(cursor)? else {
+        return None;
+    };
+    if marker.as_bytes() != ORD_MARKER {
+        return None;
+    }
+    cursor += 1;
+
+    // Read tag/field pairs up to the `OP_0` body separator
+    let mut content_type = None;
+    let mut content_encoding = None;
+    loop {
+        match instructions.get(cursor)? {
+            Instruction::PushBytes(pb) if pb.as_bytes().is_empty() => {
+                cursor += 1;
+                break;
+            }
+            Instruction::PushBytes(tag) => {
+                let tag_byte = tag.as_bytes().first().copied().unwrap_or_default();
+                cursor += 1;
+                let Instruction::PushBytes(value) = instructions.get(cursor)? else {
+                    return None;
+                };
+                match tag_byte {
+                    TAG_CONTENT_TYPE => {
+                        content_type = Some(String::from_utf8_lossy(value.as_bytes()).into_owned())
+                    }
+                    TAG_CONTENT_ENCODING => {
+                        content_encoding =
+                            Some(String::from_utf8_lossy(value.as_bytes()).into_owned())
+                    }
+                    _ => {}
+                }
+                cursor += 1;
+            }
+            _ => return None,
+        }
+    }
+
+    // Concatenate every body push up to `OP_ENDIF`
+    let mut body = Vec::new();
+    loop {
+        match instructions.get(cursor)? {
+            Instruction::Op(op) if *op == OP_ENDIF => break,
+            Instruction::PushBytes(data) => {
+                body.extend_from_slice(data.as_bytes());
+                cursor += 1;
+            }
+            _ => return None,
+        }
+    }
+
+    Some(Inscription {
+        content_type,
+        content_encoding,
+        body,
+    })
+}
+
+/// A decoded BRC-20 event, per the `{"p":"brc-20","op":...,"tick":...,"amt":...}` schema
+#[derive(Debug, Clone, Deserialize)]
+struct Brc20Event {
+    p: String,
+    op: String,
+    tick: String,
+    #[allow(unused)]
+    amt: Option<String>,
+}
+
+/// Per-block tally of BRC-20 events, grouped by `tick` and `op`
+#[derive(Debug, Default, Serialize)]
+pub struct Brc20Tally {
+    /// Count of each `(tick, op)` pair seen in the block
+    pub counts: HashMap<String, usize>,
+}
+
+/// Scans every inscription in `block`, decoding BRC-20 JSON payloads carried in
+/// `text/plain` or `application/json` inscriptions, and tallies them by tick and op.
+pub fn brc20_tally(block: &Block) -> Brc20Tally {
+    let mut tally = Brc20Tally::default();
+
+    for tx in &block.txdata {
+        for input in &tx.input {
+            let Some(inscription) = parse_inscription(input) else {
+                continue;
+            };
+            let is_text = matches!(
+                inscription.content_type.as_deref(),
+                Some("text/plain") | Some("application/json")
+            );
+            if !is_text {
+                continue;
+            }
+            let Ok(event) = serde_json::from_slice::<Brc20Event>(&inscription.body) else {
+                continue;
+            };
+            if event.p != "brc-20" {
+                continue;
+            }
+            let key = format!("{}:{}", event.tick, event.op);
+            *tally.counts.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    tally
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -135,6 +280,21 @@ mod test {
         assert!(is_ord(&tx));
     }
 
+    #[test]
+    fn parse_brc20_mint_inscription() {
+        // same payload as `arbitrary_no_checksig`: a BRC-20 mint envelope
+        let data = hex!("02000000000101dc628dbe1bd077aff4476d42e766a679645fd7012f879c8e9182e878c93d34cf1100000000ffffffff012601000000000000160014a40897ac0756778584e7dbe457cca54abc6daf4c0301024f01ac01ac880063036f726401010a746578742f706c61696e00347b2270223a226272632d3230222c226f70223a226d696e74222c227469636b223a22626e7378222c22616d74223a22313030227d6821c1782891272861d4104f524ac31855e20aa1bdb507ac4a6619c030768496b90e8400000000");
+        let tx: Transaction = bitcoin::consensus::deserialize(&data).unwrap();
+
+        let inscription = parse_inscription(&tx.input[0]).expect("envelope parses");
+        assert_eq!(inscription.content_type.as_deref(), Some("text/plain"));
+
+        let event: Brc20Event = serde_json::from_slice(&inscription.body).unwrap();
+        assert_eq!(event.p, "brc-20");
+        assert_eq!(event.op, "mint");
+        assert_eq!(event.tick, "bnsx");
+    }
+
     #[test]
     fn display_witness() {
         let data = hex!("02000000000101dc628dbe1bd077aff4476d42e766a679645fd7012f879c8e9182e878c93d34cf1100000000ffffffff012601000000000000160014a40897ac0756778584e7dbe457cca54abc6daf4c0301024f01ac01ac880063036f726401010a746578742f706c61696e00347b2270223a226272632d3230222c226f70223a226d696e74222c227469636b223a22626e7378222c22616d74223a22313030227d6821c1782891272861d4104f524ac31855e20aa1bdb507ac4a6619c030768496b90e8400000000");
@@ -0,0 +1,217 @@
+use super::*;
+use bitcoin::{Block, BlockHash};
+use bitcoincore_rpc_json::{GetBlockTemplateModes, GetBlockTemplateRules};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of distinct tips to retain templates for. Once a poll observes a tip beyond
+/// this many templates deep, the oldest is evicted.
+const TEMPLATE_HISTORY: usize = 6;
+
+/// A single polled `getblocktemplate` snapshot
+#[derive(Debug, Clone)]
+struct Template {
+    previous_block_hash: BlockHash,
+    txids: Vec<Txid>,
+    polled_at: u64,
+}
+
+/// Maintains a rolling cache of `getblocktemplate` snapshots so [`crate::block_audit`] can
+/// score newly connected blocks without requiring the poll and the confirmation to happen
+/// in the same process run. Unconfirmed projections carry forward across polls until the
+/// block that consumes them is seen, mirroring the [`crate::watch::Watcher`] cache pattern.
+/// Driven by the `audit template` command, which polls on a timer and scores each newly
+/// connected tip as it's observed.
+#[derive(Default)]
+pub struct TemplateTracker {
+    templates: Vec<Template>,
+}
+
+impl TemplateTracker {
+    /// Creates an empty [`TemplateTracker`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls `core` for the current block template, storing its projected txid set and
+    /// the time it was captured. A template for the same tip replaces the previous one;
+    /// once more than [`TEMPLATE_HISTORY`] distinct tips have been seen, the oldest is evicted.
+    pub fn poll(&mut self, core: &Client) -> Result<()> {
+        let template = core.get_block_template(
+            GetBlockTemplateModes::Template,
+            &[GetBlockTemplateRules::SegWit],
+            &[],
+        )?;
+
+        let polled_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time before unix epoch")
+            .as_secs();
+        let txids = template.transactions.iter().map(|tx| tx.txid).collect();
+
+        self.record(template.previous_block_hash, txids, polled_at);
+        Ok(())
+    }
+
+    /// Inserts a polled snapshot, replacing any existing entry for the same tip and
+    /// evicting the oldest once more than [`TEMPLATE_HISTORY`] distinct tips are held.
+    fn record(&mut self, previous_block_hash: BlockHash, txids: Vec<Txid>, polled_at: u64) {
+        self.templates
+            .retain(|t| t.previous_block_hash != previous_block_hash);
+        self.templates.push(Template {
+            previous_block_hash,
+            txids,
+            polled_at,
+        });
+
+        if self.templates.len() > TEMPLATE_HISTORY {
+            self.templates.remove(0);
+        }
+    }
+
+    /// Selects the most recent template whose `previousblockhash` matches `block`'s parent
+    /// and feeds its projected txids into [`crate::block_audit`]. Returns `None` if no
+    /// matching template was polled before the block connected.
+    pub fn audit_connected(&self, block: &Block) -> Option<f64> {
+        let parent = block.header.prev_blockhash;
+        let template = self
+            .templates
+            .iter()
+            .rev()
+            .find(|t| t.previous_block_hash == parent)?;
+        Some(block_audit(block, &template.txids))
+    }
+
+    /// Like [`Self::audit_connected`], but returns the full [`crate::report::BlockAuditReport`]
+    /// via [`crate::block_audit_report`] instead of just the score, so a caller can build up
+    /// a time series via [`crate::report::export`].
+    pub fn report_connected(&self, height: u64, block: &Block) -> Option<report::BlockAuditReport> {
+        let parent = block.header.prev_blockhash;
+        let template = self
+            .templates
+            .iter()
+            .rev()
+            .find(|t| t.previous_block_hash == parent)?;
+        Some(block_audit_report(height, block, &template.txids))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitcoin::block::{Header, Version};
+    use bitcoin::hashes::Hash;
+    use bitcoin::pow::CompactTarget;
+    use bitcoin::{
+        absolute::LockTime, transaction, Amount, OutPoint, ScriptBuf, Sequence, TxIn,
+        TxMerkleNode, Transaction, TxOut, Witness,
+    };
+
+    fn block_with_parent(parent: BlockHash) -> Block {
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array([1u8; 32]),
+                    vout: 0,
+                },
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1_000),
+                script_pubkey: ScriptBuf::default(),
+            }],
+        };
+
+        Block {
+            header: Header {
+                version: Version::ONE,
+                prev_blockhash: parent,
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: vec![tx],
+        }
+    }
+
+    #[test]
+    fn record_replaces_entry_for_same_tip_instead_of_duplicating() {
+        let mut tracker = TemplateTracker::new();
+        let tip = BlockHash::all_zeros();
+
+        tracker.record(tip, vec![], 1);
+        tracker.record(tip, vec![], 2);
+
+        assert_eq!(tracker.templates.len(), 1);
+        assert_eq!(tracker.templates[0].polled_at, 2);
+    }
+
+    #[test]
+    fn record_evicts_oldest_tip_once_history_exceeded() {
+        let mut tracker = TemplateTracker::new();
+
+        for i in 0..=TEMPLATE_HISTORY {
+            let hash = BlockHash::from_byte_array([i as u8; 32]);
+            tracker.record(hash, vec![], i as u64);
+        }
+
+        assert_eq!(tracker.templates.len(), TEMPLATE_HISTORY);
+        let oldest = BlockHash::from_byte_array([0u8; 32]);
+        assert!(!tracker.templates.iter().any(|t| t.previous_block_hash == oldest));
+    }
+
+    #[test]
+    fn audit_connected_finds_the_template_matching_the_blocks_parent() {
+        let mut tracker = TemplateTracker::new();
+        let parent = BlockHash::from_byte_array([7u8; 32]);
+        let block = block_with_parent(parent);
+
+        tracker.record(parent, vec![block.txdata[0].txid()], 1);
+
+        assert!(tracker.audit_connected(&block).is_some());
+    }
+
+    #[test]
+    fn audit_connected_is_none_without_a_matching_template() {
+        let tracker = TemplateTracker::new();
+        let block = block_with_parent(BlockHash::all_zeros());
+
+        assert!(tracker.audit_connected(&block).is_none());
+    }
+
+    #[test]
+    fn poll_fetches_and_records_the_current_template() {
+        use bitcoincore_rpc::Auth;
+
+        let user = env!("RPC_USER");
+        let pass = env!("RPC_PASS");
+        let client = Client::new(
+            "127.0.0.1:8332",
+            Auth::UserPass(user.to_string(), pass.to_string()),
+        )
+        .unwrap();
+
+        let mut tracker = TemplateTracker::new();
+        tracker.poll(&client).expect("poll the current template");
+        assert_eq!(tracker.templates.len(), 1);
+    }
+
+    #[test]
+    fn report_connected_reflects_the_matching_templates_projection() {
+        let mut tracker = TemplateTracker::new();
+        let parent = BlockHash::from_byte_array([7u8; 32]);
+        let block = block_with_parent(parent);
+
+        tracker.record(parent, vec![block.txdata[0].txid()], 1);
+
+        let report = tracker.report_connected(100, &block).expect("matching template");
+        assert_eq!(report.height, 100);
+        assert_eq!(report.projected_count, 1);
+        assert_eq!(report.actual_count, 1);
+        assert_eq!(report.unseen_count, 0);
+    }
+}
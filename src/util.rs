@@ -1,7 +1,19 @@
 use super::*;
+use bitcoin::opcodes::all::{OP_PUSHNUM_1, OP_PUSHNUM_16};
+use bitcoin::opcodes::Opcode;
 use std::cmp::Ordering;
 use std::hash::Hash;
 
+/// Returns `N` if `op` is `OP_PUSHNUM_N` for `1 <= N <= 16`, else `None`
+pub fn pushnum_value(op: Opcode) -> Option<u32> {
+    let b = op.to_u8();
+    if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&b) {
+        Some(u32::from(b - OP_PUSHNUM_1.to_u8() + 1))
+    } else {
+        None
+    }
+}
+
 /// Creates a "reverse" index by mapping keys of the given `map`
 /// to the index value obtained by iterating it.
 pub fn key_index<'a, T, M>(map: impl IntoIterator<Item = (&'a T, &'a M)>) -> HashMap<T, usize>
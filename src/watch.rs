@@ -0,0 +1,254 @@
+use super::*;
+use bitcoin::{Block, ScriptBuf, Transaction};
+use serde::Serialize;
+
+/// Number of confirmed blocks to scan backward from the chain tip when rebuilding
+/// the confirmed portion of a [`Watcher`]'s cache, or when walking history in
+/// [`Watcher::scan_recent`].
+const SAFETY_MARGIN: u32 = 6;
+
+/// A payment observed to one of a [`Watcher`]'s scriptPubKeys
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchResult {
+    pub destination_script: ScriptBuf,
+    pub confirmations: u32,
+    pub value: bitcoin::Amount,
+    pub txid: bitcoin::Txid,
+    pub vout: u32,
+}
+
+/// A single matching output seen by [`Watcher::scan_recent`]. Unlike [`WatchResult`], which
+/// `poll` caches one per script, a [`DepositHit`] is reported for every match, so a caller
+/// can follow a deposit from first-seen through N confirmations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DepositHit {
+    pub outpoint: bitcoin::OutPoint,
+    pub script: ScriptBuf,
+    pub value: bitcoin::Amount,
+    pub confirmations: u32,
+}
+
+/// Maintains a rolling cache of payments to a set of watched scriptPubKeys, spanning
+/// both the mempool (0-conf) and the last [`SAFETY_MARGIN`] confirmed blocks. Where the
+/// node serves BIP158 filters, confirmed blocks are filtered via [`crate::filter`] before
+/// being fetched in full, so scanning a tall `SAFETY_MARGIN` doesn't mean downloading every
+/// block in it.
+pub struct Watcher {
+    scripts: HashSet<ScriptBuf>,
+    cache: HashMap<ScriptBuf, WatchResult>,
+}
+
+impl Watcher {
+    /// Creates a new [`Watcher`] over the given set of scriptPubKeys
+    pub fn new(scripts: HashSet<ScriptBuf>) -> Self {
+        Self {
+            scripts,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Polls `core` for payments to the watched scripts. The confirmed portion of the
+    /// cache is rebuilt fresh on every call (so a reorged entry simply drops out),
+    /// while 0-conf entries are taken directly from the current mempool. Returns the
+    /// entries that are new or whose confirmation depth has changed since the last poll.
+    pub fn poll(&mut self, core: &Client) -> Result<Vec<WatchResult>> {
+        let tip = core.get_block_count()?;
+        let mut next_cache: HashMap<ScriptBuf, WatchResult> = HashMap::new();
+
+        // Confirmed portion: walk backward from the tip for `confirmations` in 1..=SAFETY_MARGIN
+        for confirmations in 1..=SAFETY_MARGIN {
+            let height = tip.saturating_sub(u64::from(confirmations) - 1);
+            let hash = core.get_block_hash(height)?;
+            if !self.might_match(core, &hash) {
+                continue;
+            }
+            let block = core.get_block(&hash)?;
+            self.scan_block(&block, confirmations, &mut next_cache);
+        }
+
+        // 0-conf portion: ingest the mempool
+        let mempool = core.get_raw_mempool_verbose()?;
+        for txid in mempool.keys() {
+            let Ok(tx) = core.get_raw_transaction(txid, None) else {
+                continue;
+            };
+            self.scan_tx(&tx, 0, &mut next_cache);
+        }
+
+        let changed: Vec<WatchResult> = next_cache
+            .iter()
+            .filter(|(spk, result)| self.cache.get(*spk) != Some(*result))
+            .map(|(_, result)| result.clone())
+            .collect();
+
+        self.cache = next_cache;
+        Ok(changed)
+    }
+
+    /// Walks the last [`SAFETY_MARGIN`] confirmed blocks from the chain tip, collecting
+    /// every matching output rather than `poll`'s latest-per-script cache, so a caller can
+    /// follow a deposit from first-seen through N confirmations without re-querying per
+    /// script.
+    pub fn scan_recent(&self, core: &Client) -> Result<Vec<DepositHit>> {
+        let tip_height = core.get_block_count()?;
+        let tip_height = u32::try_from(tip_height).expect("block height fits in u32");
+        let mut hits = Vec::new();
+
+        for confirmations in 1..=SAFETY_MARGIN {
+            let block_height = tip_height.saturating_sub(confirmations - 1);
+            let hash = core.get_block_hash(u64::from(block_height))?;
+            if !self.might_match(core, &hash) {
+                continue;
+            }
+            let block = core.get_block(&hash)?;
+            hits.extend(self.scan_block_hits(&block, tip_height, block_height));
+        }
+
+        Ok(hits)
+    }
+
+    /// Consults the node's BIP158 block filter for `hash` (via `getblockfilter`, which
+    /// requires `-blockfilterindex=1`) to decide whether the block is worth fetching in
+    /// full. Falls back to `true` -- always fetch -- if the node doesn't serve filters, so
+    /// a `Watcher` against a default-configured node behaves exactly as before.
+    fn might_match(&self, core: &Client, hash: &bitcoin::BlockHash) -> bool {
+        let Ok(res) = core.get_block_filter(hash) else {
+            return true;
+        };
+        let targets: Vec<ScriptBuf> = self.scripts.iter().cloned().collect();
+        crate::filter::filter_matches(&res.filter, *hash, &targets)
+    }
+
+    fn scan_block(&self, block: &Block, confirmations: u32, cache: &mut HashMap<ScriptBuf, WatchResult>) {
+        for tx in &block.txdata {
+            self.scan_tx(tx, confirmations, cache);
+        }
+    }
+
+    fn scan_tx(&self, tx: &Transaction, confirmations: u32, cache: &mut HashMap<ScriptBuf, WatchResult>) {
+        for (vout, txo) in tx.output.iter().enumerate() {
+            if self.scripts.contains(&txo.script_pubkey) {
+                cache.insert(
+                    txo.script_pubkey.clone(),
+                    WatchResult {
+                        destination_script: txo.script_pubkey.clone(),
+                        confirmations,
+                        value: txo.value,
+                        txid: tx.txid(),
+                        vout: vout as u32,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Scans `block`, connected at `block_height`, for outputs paying one of the watched
+    /// scripts, returning one [`DepositHit`] per match with `confirmations` computed
+    /// relative to `tip_height`.
+    fn scan_block_hits(&self, block: &Block, tip_height: u32, block_height: u32) -> Vec<DepositHit> {
+        let confirmations = tip_height.saturating_sub(block_height) + 1;
+        let mut hits = Vec::new();
+
+        for tx in &block.txdata {
+            for (vout, txo) in tx.output.iter().enumerate() {
+                if self.scripts.contains(&txo.script_pubkey) {
+                    hits.push(DepositHit {
+                        outpoint: bitcoin::OutPoint {
+                            txid: tx.txid(),
+                            vout: vout as u32,
+                        },
+                        script: txo.script_pubkey.clone(),
+                        value: txo.value,
+                        confirmations,
+                    });
+                }
+            }
+        }
+
+        hits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scan_tx_matches_watched_script() {
+        use bitcoin::{absolute::LockTime, transaction, Amount, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Witness};
+
+        let watched = ScriptBuf::from_hex("0014170ef448a233262c316d983f3f76ff9941df5e17").unwrap();
+        let mut scripts = HashSet::new();
+        scripts.insert(watched.clone());
+        let watcher = Watcher::new(scripts);
+
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1_000),
+                script_pubkey: watched.clone(),
+            }],
+        };
+
+        let mut cache = HashMap::new();
+        watcher.scan_tx(&tx, 0, &mut cache);
+        let hit = cache.get(&watched).expect("watched script seen");
+        assert_eq!(hit.confirmations, 0);
+        assert_eq!(hit.value, Amount::from_sat(1_000));
+    }
+
+    #[test]
+    fn scan_block_hits_reports_confirmations_relative_to_tip() {
+        use bitcoin::block::{Header, Version};
+        use bitcoin::hashes::Hash;
+        use bitcoin::pow::CompactTarget;
+        use bitcoin::{
+            absolute::LockTime, transaction, Amount, BlockHash, OutPoint, ScriptBuf, Sequence,
+            TxIn, TxMerkleNode, TxOut, Witness,
+        };
+
+        let watched = ScriptBuf::from_hex("0014170ef448a233262c316d983f3f76ff9941df5e17").unwrap();
+        let mut scripts = HashSet::new();
+        scripts.insert(watched.clone());
+        let watcher = Watcher::new(scripts);
+
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1_000),
+                script_pubkey: watched.clone(),
+            }],
+        };
+        let block = Block {
+            header: Header {
+                version: Version::ONE,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: vec![tx.clone()],
+        };
+
+        let hits = watcher.scan_block_hits(&block, 106, 100);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].outpoint, OutPoint { txid: tx.txid(), vout: 0 });
+        assert_eq!(hits[0].confirmations, 7);
+        assert_eq!(hits[0].value, Amount::from_sat(1_000));
+    }
+}